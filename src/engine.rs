@@ -1,3 +1,4 @@
 pub mod line_stuff;
 pub mod scene;
-pub mod model;
\ No newline at end of file
+pub mod model;
+pub mod replay;
\ No newline at end of file
@@ -46,9 +46,11 @@ impl LineListIndex {
 
 impl From<LineListIndex> for Mesh {
     fn from(value: LineListIndex) -> Self {
+        //kept in the main world as well, a mesh only used by the render world is removed from the mesh 
+        //assets once it has been sent to the gpu, which looks as if it had been unloaded
         Mesh::new(
             PrimitiveTopology::LineList,
-            RenderAssetUsages::RENDER_WORLD
+            RenderAssetUsages::default()
         )
         .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, value.points)
         .with_inserted_indices(bevy::render::mesh::Indices::U32(value.indices))
@@ -1,77 +1,198 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
 use rand::Rng;
-use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+pub mod randomizer;
+pub mod scoring;
+
+use randomizer::{Randomizer, RandomizerKind};
+use scoring::{AttackTable, Score, ScoringRules, TSpin};
 
 const NBR_OF_TETROMINUS: u32 = 7;
 pub const TETRIS_FIELD_DEFAULT_WIDTH: u32 = 10;
 pub const TETRIS_FIELD_DEFAULT_HEIGHT: u32 = 20;
-const TETRIS_FIELD_LENGTH: usize = (TETRIS_FIELD_DEFAULT_WIDTH * TETRIS_FIELD_DEFAULT_HEIGHT) as usize;
+pub const TETRIS_FIELD_MIN_WIDTH: u32 = 4;
+pub const TETRIS_FIELD_MIN_HEIGHT: u32 = 4;
+pub const LOCK_DELAY_DEFAULT_MILLIS: u32 = 500;
+pub const LOCK_RESET_MOVE_LIMIT: u32 = 15;
+pub const MAX_PREVIEW_COUNT: u32 = 7;
 
 
 /// The main Tetris struct of the underlying model. This model is independent of the rest of the bevy engine 
 /// usage and could theoretically without much effort be ported to another user wrapper. This model is 
 /// completely inert and has to be interacted with via its public interface. 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Tetris<T: Rng + Sized + Send> {
     field: TetrisField,
     active_piece: PhysicalTetromino,
-    next_piece: PhysicalTetromino,
-    stored_piece: PhysicalTetromino,
+    stored_piece: Option<PhysicalTetromino>,
     ghost_piece: PhysicalTetromino,
     iterator: TetrominoIterator<T>,
+    rules: TetrisRules,
     switchted_active_piece_since_last_drop: bool,
+    //events are drained every frame, so there is nothing to save
+    #[serde(skip)]
+    events: Vec<GameEvent>,
+    score: Score,
+    last_rotation_kick: Option<(i32, i32)>,
+    lock_timer: Duration,
+    lock_resets: u32,
+    lowest_row: i32,
+    #[serde(default)]
+    pending_garbage: VecDeque<GarbageRows>,
 }
 
 impl<T: Rng + Sized + Send> Tetris<T> {
     /// Creates a new instance. Takes a random number generator as argument for possible repeatability with a 
-    /// given seed. The active, next and stored piece are immediately determined using the rng. 
-    pub fn new(rng: T) -> Self {
-        let mut iterator = TetrominoIterator::new(rng);
-        let mut field = [CellStatus::Empty; TETRIS_FIELD_LENGTH].into();
-        let active_piece = Tetris::<T>::place_tetromino_on_field(&mut field, (&mut iterator).next().unwrap());
-        let next_piece = Tetris::<T>::tetromino_to_physical((&mut iterator).next().unwrap());
-        let stored_piece = Tetris::<T>::tetromino_to_physical((&mut iterator).next().unwrap());
+    /// given seed and the [TetrisRules] the game is played with. Field dimensions below the minimum are 
+    /// raised to it. The active and next pieces are immediately determined using the rng and the 
+    /// randomizer of the rules, the hold starts out empty. The field starts out empty as well, see 
+    /// [Tetris::add_cheese_rows]. 
+    pub fn new(rng: T, rules: TetrisRules) -> Self {
+        Tetris::with_randomizer(rng, rules, rules.randomizer.randomizer())
+    }
+
+    /// Creates a new instance like [Tetris::new], but deals the pieces with the given randomizer instead 
+    /// of the one of the rules. 
+    pub fn with_randomizer(rng: T, mut rules: TetrisRules, randomizer: Box<dyn Randomizer>) -> Self {
+        rules.field_width = rules.field_width.max(TETRIS_FIELD_MIN_WIDTH);
+        rules.field_height = rules.field_height.max(TETRIS_FIELD_MIN_HEIGHT);
+        let rotation_system = rules.rotation_system;
+
+        let mut iterator = TetrominoIterator::new(rng, randomizer);
+        let field = TetrisField::new(rules.field_width, rules.field_height);
+        let active_piece = Tetris::<T>::place_tetromino_on_field(&field, (&mut iterator).next().unwrap(), rotation_system)
+            .expect("A tetromino always fits into an empty field");
         let ghost_piece = Tetris::<T>::find_ghost_piece_pos(&field, &active_piece);
 
         Self {
             field,
             active_piece,
-            next_piece,
-            stored_piece,
+            stored_piece: None,
             ghost_piece,
             iterator,
+            rules,
             switchted_active_piece_since_last_drop: false,
+            events: vec![GameEvent::PieceSpawned(active_piece.tetromino)],
+            score: Score::default(),
+            last_rotation_kick: None,
+            lock_timer: Duration::ZERO,
+            lock_resets: 0,
+            lowest_row: active_piece.lowest_row(),
+            pending_garbage: VecDeque::new(),
+        }
+    }
+
+    /// Inserts the cheese rows of the rules at the bottom of the field, each with its own random hole. 
+    /// Meant to be called right after creating the game. The holes are drawn from the given rng, which 
+    /// should not be the one dealing the pieces, so the same seed deals the same pieces regardless of the 
+    /// number of cheese rows. 
+    pub fn add_cheese_rows(&mut self, rng: &mut impl Rng) {
+        for _ in 0..self.rules.cheese_rows.min(self.rules.field_height / 2) {
+            let hole_column = rng.gen_range(0..self.rules.field_width);
+            self.add_garbage(1, hole_column);
         }
+        self.apply_pending_garbage()
+            .expect("The cheese rows always fit below the spawn position");
+        self.refresh_ghost_piece();
     }
 
-    /// Tries to switch the active piece. Returns Err when piece has already been switched before the active 
-    /// piece has been dropped.
-    pub fn try_switch_active_piece(&mut self) -> Result<(), ()> {
-        if self.switchted_active_piece_since_last_drop {
-            return Err(());
+    /// Advances the time of the game by the given duration. While the active piece rests on the ground, 
+    /// this runs down its lock delay and locks it once the delay has passed. Returns the result of the 
+    /// lock if the piece was locked, Err with the reason if this ends the game. 
+    pub fn tick(&mut self, delta: Duration) -> Result<Option<LockResult>, GameOver> {
+        if self.rules.lock_delay_millis == 0 || !self.is_grounded() {
+            self.lock_timer = Duration::ZERO;
+            return Ok(None);
         }
 
-        //clear board of active piece
-        for pos in self.active_piece.coords {
-            if let Some(cell) = self.field.get_mut(pos.x, pos.y) {
-                *cell = CellStatus::Empty;
-            }
+        self.lock_timer += delta;
+        let out_of_resets = self.rules.lock_reset == LockReset::Move && self.lock_resets >= LOCK_RESET_MOVE_LIMIT;
+        if out_of_resets || self.lock_timer >= Duration::from_millis(self.rules.lock_delay_millis as u64) {
+            return self.lock_active_piece().map(Some);
         }
 
+        Ok(None)
+    }
+
+    /// Queues the given number of garbage rows, which are inserted at the bottom of the field when the 
+    /// next piece locks. Every row is full except for the cell in the hole column, columns outside the 
+    /// field are moved to the rightmost column. 
+    pub fn add_garbage(&mut self, rows: u32, hole_column: u32) {
+        let hole_column = hole_column.min(self.field.width - 1);
+        self.pending_garbage.push_back(GarbageRows { rows, hole_column });
+    }
+
+    /// Returns the current score, level and cleared lines of the game. 
+    pub fn score(&self) -> &Score {
+        &self.score
+    }
+
+    /// Gives up the game, which ends it like topping out. 
+    pub fn forfeit(&mut self) {
+        self.events.push(GameEvent::GameOver(GameOver::Forfeit));
+    }
+
+    /// Removes and returns all events that happened since the last call, in the order they happened. 
+    pub fn drain_events(&mut self) -> std::vec::Drain<'_, GameEvent> {
+        self.events.drain(..)
+    }
+
+    /// Tries to switch the active piece with the stored piece. While the hold is empty, the next piece is 
+    /// pulled in instead. Returns Err when the hold rule forbids holding, when the piece has already been 
+    /// switched before the active piece has been dropped or when the incoming piece could not be placed 
+    /// on top of the field. 
+    pub fn try_switch_active_piece(&mut self) -> Result<(), MoveError> {
+        match self.rules.hold {
+            HoldRule::Disabled => return Err(MoveError::HoldDisabled),
+            HoldRule::Once if self.switchted_active_piece_since_last_drop => return Err(MoveError::HoldAlreadyUsed),
+            HoldRule::Once | HoldRule::Unlimited => {}
+        }
+
+        let incoming = match self.stored_piece {
+            Some(stored_piece) => stored_piece.tetromino,
+            None => *self.iterator.peek().next().expect("The preview queue is never empty"),
+        };
         let old_active = self.active_piece.tetromino;
-        self.active_piece = Tetris::<T>::place_tetromino_on_field(&mut self.field, self.stored_piece.tetromino);
-        self.stored_piece = Tetris::<T>::tetromino_to_physical(old_active);
+        self.active_piece = Tetris::<T>::place_tetromino_on_field(&self.field, incoming, self.rules.rotation_system)
+            .map_err(|_| MoveError::BlockedByStack)?;
+        //the next piece is only taken out of the queue once it fits
+        let pulled_next_piece = self.stored_piece.is_none();
+        if pulled_next_piece {
+            (&mut self.iterator).next();
+        }
+        self.stored_piece = Some(Tetris::<T>::tetromino_to_physical(old_active, self.rules.rotation_system));
         self.switchted_active_piece_since_last_drop = true;
+        self.last_rotation_kick = None;
+        self.restart_lock_delay();
         self.refresh_ghost_piece();
+        self.events.push(GameEvent::Held(old_active));
+        if pulled_next_piece {
+            self.events.push(GameEvent::PieceSpawned(incoming));
+        }
         Ok(())
     }
 
-    /// Returns a list of all occupied cells in the tetris field, along with which piece occupies it. The lower
-    /// left corner of its possible positions is its (0, 0) point. 
+    /// Returns the width of the playfield in cells. 
+    pub fn field_width(&self) -> u32 {
+        self.field.width
+    }
+
+    /// Returns the height of the playfield in cells. 
+    pub fn field_height(&self) -> u32 {
+        self.field.height
+    }
+
+    /// Returns a list of all locked cells in the tetris field, along with which piece occupies it. The 
+    /// active piece is not part of this list, see [Tetris::get_active_piece_list]. The lower left corner 
+    /// of its possible positions is its (0, 0) point. 
     pub fn get_block_list(&self) -> Vec<(CellStatus, u32, u32)> {
         let mut vec = Vec::new();
 
-        for y in 0..TETRIS_FIELD_DEFAULT_HEIGHT {
-            for x in 0..TETRIS_FIELD_DEFAULT_WIDTH {
+        for y in 0..self.field.height {
+            for x in 0..self.field.width {
                 let elem = self.field.get(x as i32, y as i32 ).unwrap();
                 if elem != CellStatus::Empty {
                     vec.push((elem, x, y));
@@ -82,27 +203,48 @@ impl<T: Rng + Sized + Send> Tetris<T> {
         vec
     }
 
-    /// Returns the list of 4 cells occupied by the next block. The lower left corner of possible positions is
-    /// its (0, 0) point. 
-    pub fn get_next_block_list(&self) -> [(CellStatus, u32, u32); 4] {
-        let mut arr: [(CellStatus, u32, u32); 4] = [(CellStatus::Empty, 0, 0); 4];
-        
-        for (index, pos) in self.next_piece.coords.iter().enumerate() {
-            arr[index] = (self.next_piece.color, pos.x as u32, pos.y as u32);
-        }
+    /// Returns the list of cells occupied by the active piece which are inside the tetris field. Cells 
+    /// above the top of the field are left out. The lower left corner of possible positions is its 
+    /// (0, 0) point. 
+    pub fn get_active_piece_list(&self) -> Vec<(CellStatus, u32, u32)> {
+        self.active_piece.coords
+            .iter()
+            .filter(|pos| self.field.get(pos.x, pos.y).is_some())
+            .map(|pos| (self.active_piece.color, pos.x as u32, pos.y as u32))
+            .collect()
+    }
 
-        arr
+    /// Returns the lists of 4 cells occupied by each of the given number of upcoming blocks, in the order 
+    /// they will be played. The count is limited to 1 to [MAX_PREVIEW_COUNT]. The lower left corner of 
+    /// possible positions is the (0, 0) point of each list. 
+    pub fn get_next_block_lists(&self, count: u32) -> Vec<[(CellStatus, u32, u32); 4]> {
+        let count = count.clamp(1, MAX_PREVIEW_COUNT) as usize;
+
+        self.iterator.peek()
+            .take(count)
+            .map(|tetromino| {
+                let piece = Tetris::<T>::tetromino_to_physical(*tetromino, self.rules.rotation_system);
+                let mut arr: [(CellStatus, u32, u32); 4] = [(CellStatus::Empty, 0, 0); 4];
+
+                for (index, pos) in piece.coords.iter().enumerate() {
+                    arr[index] = (piece.color, pos.x as u32, pos.y as u32);
+                }
+
+                arr
+            })
+            .collect()
     }
 
-    /// Returns the list of 4 cells occupied by the stored block. 
-    pub fn get_stored_block_list(&self) -> [(CellStatus, u32, u32); 4] {
+    /// Returns the list of 4 cells occupied by the stored block, or None while the hold is empty. 
+    pub fn get_stored_block_list(&self) -> Option<[(CellStatus, u32, u32); 4]> {
+        let stored_piece = self.stored_piece.as_ref()?;
         let mut arr: [(CellStatus, u32, u32); 4] = [(CellStatus::Empty, 0, 0); 4];
         
-        for (index, pos) in self.stored_piece.coords.iter().enumerate() {
-            arr[index] = (self.stored_piece.color, pos.x as u32, pos.y as u32);
+        for (index, pos) in stored_piece.coords.iter().enumerate() {
+            arr[index] = (stored_piece.color, pos.x as u32, pos.y as u32);
         }
 
-        arr
+        Some(arr)
     }
 
     /// Returns the list of 4 cells occupied by the ghost block. 
@@ -116,62 +258,84 @@ impl<T: Rng + Sized + Send> Tetris<T> {
         arr
     }
 
-    /// Tries to drop the piece. Returns Err if the dropped piece was above the playingfield. This signals the 
-    /// end of the round. If the drop was successfull returns true for nothing else happening and false for 
-    /// some cleared lines, along with the number. 
-    pub fn drop(&mut self) -> Result<(bool, Option<u32>), ()> {
-        //let drop_result = Tetris::<T>::try_drop(&mut self.field, &mut self.active_piece);
-        let drop_result = Tetris::<T>::try_move(&mut self.field, &mut self.active_piece, Direction::Down);
+    /// Tries to drop the piece by one cell. If the piece is already at the bottom and the rules have no 
+    /// lock delay, it is locked into the field, full lines are cleared and the next piece is spawned. 
+    /// With a lock delay, locking is left to [Tetris::tick]. Returns Err with the reason if this ends 
+    /// the game. 
+    pub fn drop(&mut self) -> Result<DropOutcome, GameOver> {
+        let drop_result = Tetris::<T>::try_move(&self.field, &mut self.active_piece, Direction::Down);
         match drop_result {
             Ok(_) => {
                 //successfull drop, nothing else to be done
-                Ok((true, None))
+                self.last_rotation_kick = None;
+                self.events.push(GameEvent::Moved(Direction::Down));
+                if self.active_piece.lowest_row() < self.lowest_row {
+                    //reaching a new row gives the piece its full lock delay and all resets back
+                    self.lowest_row = self.active_piece.lowest_row();
+                    self.restart_lock_delay();
+                }
+                Ok(DropOutcome::Moved)
+            }
+            Err(_) if self.rules.lock_delay_millis > 0 => {
+                //piece is already at the bottom, but may still be moved until the lock delay has passed
+                Ok(DropOutcome::Grounded)
             }
             Err(_) => {
                 //piece is already at the bottom
-
-                //check if pixel of any piece is outside of the playingfield
-                for pos in self.active_piece.coords {
-                    if pos.y >= TETRIS_FIELD_DEFAULT_HEIGHT as i32 {
-                        return Err(());
-                    }
-                }
-
-                //do the usual cleanup and spawn new piece at the top
-                self.switchted_active_piece_since_last_drop = false;
-                let nbr_of_lines = self.check_for_lines_and_clear();
-                self.next_piece();
-                self.refresh_ghost_piece();
-                Ok((false, Some(nbr_of_lines)))
+                Ok(DropOutcome::Locked(self.lock_active_piece()?))
             }
         }
     }
 
-    /// Tries to move the piece to the left. Returns Ok if successfull, Err otherwise.
-    pub fn try_left(&mut self) -> Result<(), ()> {
-        Tetris::<T>::try_move(&mut self.field, &mut self.active_piece, Direction::Left)?;
+    /// Drops the piece by one cell like [Tetris::drop], but as a player input which is rewarded with 
+    /// points if the piece moved. 
+    pub fn soft_drop(&mut self) -> Result<DropOutcome, GameOver> {
+        let outcome = self.drop()?;
+        if outcome == DropOutcome::Moved {
+            self.score.add_drop(self.rules.scoring, 1, false);
+        }
+        Ok(outcome)
+    }
+
+    /// Tries to move the piece to the left. Returns Ok if successfull, Err with the reason otherwise.
+    pub fn try_left(&mut self) -> Result<(), MoveError> {
+        Tetris::<T>::try_move(&self.field, &mut self.active_piece, Direction::Left)?;
+        self.last_rotation_kick = None;
+        self.reset_lock_delay_after_move();
         self.refresh_ghost_piece();
+        self.events.push(GameEvent::Moved(Direction::Left));
         Ok(())
     }
 
-    /// Tries to move the piece to the right. Returns Ok if successfull, Err otherwise. 
-    pub fn try_right(&mut self) -> Result<(), ()> {
-        Tetris::<T>::try_move(&mut self.field, &mut self.active_piece, Direction::Right)?;
+    /// Tries to move the piece to the right. Returns Ok if successfull, Err with the reason otherwise. 
+    pub fn try_right(&mut self) -> Result<(), MoveError> {
+        Tetris::<T>::try_move(&self.field, &mut self.active_piece, Direction::Right)?;
+        self.last_rotation_kick = None;
+        self.reset_lock_delay_after_move();
         self.refresh_ghost_piece();
+        self.events.push(GameEvent::Moved(Direction::Right));
         Ok(())
     }
     
-    /// Tries to drop the piece all the way down. Returns Err if the piece ended up above the playfield, 
-    /// thereby ending the game. Else returns Ok(number of dropped cells, number of cleared lines). 
-    pub fn drop_completely_down(&mut self) -> Result<(u32, u32), ()> {
+    /// Tries to drop the piece all the way down and locks it. Every dropped cell is rewarded with points. 
+    /// Returns Err with the reason if this ends the game. Else returns the number of dropped cells along 
+    /// with the result of the lock. 
+    pub fn drop_completely_down(&mut self) -> Result<HardDropOutcome, GameOver> {
         let mut dropped_cell_counter = 0;
-        loop {
-            let result = self.drop()?;
-            if let (false, Some(nbr_of_cleared_lines)) = result {
-                return Ok((dropped_cell_counter, nbr_of_cleared_lines))
-            }
+        while Tetris::<T>::try_move(&self.field, &mut self.active_piece, Direction::Down).is_ok() {
             dropped_cell_counter += 1;
         }
+        if dropped_cell_counter > 0 {
+            self.last_rotation_kick = None;
+        }
+        self.score.add_drop(self.rules.scoring, dropped_cell_counter, true);
+        self.events.push(GameEvent::HardDropped(dropped_cell_counter));
+
+        let lock_result = self.lock_active_piece()?;
+        Ok(HardDropOutcome {
+            cells_dropped: dropped_cell_counter,
+            lock_result,
+        })
     }
 
     /// Tries to spin the active piece clockwise. Does nothing if the piece cant be rotated.
@@ -184,52 +348,62 @@ impl<T: Rng + Sized + Send> Tetris<T> {
         let _ = self.try_spin(SpinDirection::CounterClockwise);
     }
 
-    /// Places a new Tetromino on top of the field. Immediately tries to move it down into the field but 
-    /// does nothing else on failure. Then returns its PhysicalTetromino representation. 
-    fn place_tetromino_on_field(field: &mut TetrisField, tetromino: Tetromino) -> PhysicalTetromino {
-        let half_width = TETRIS_FIELD_DEFAULT_WIDTH / 2 - 1;
+    /// Tries to spin the active piece by 180°. The half turn has its own wall kicks, so it can succeed 
+    /// where two quarter turns in a row would not. Does nothing if the piece cant be rotated. 
+    pub fn spin_180(&mut self) {
+        let _ = self.try_spin(SpinDirection::Half);
+    }
+
+    /// Places a new Tetromino on top of the field and moves it down into its spawn position. Returns 
+    /// its PhysicalTetromino representation, or Err if the spawn position is blocked by the stack. 
+    fn place_tetromino_on_field(field: &TetrisField, tetromino: Tetromino, rotation_system: RotationSystemKind) -> Result<PhysicalTetromino, GameOver> {
+        let half_width = field.width / 2 - 1;
+        let height = field.height as i32;
 
-        let mut phys_tetromino = Tetris::<T>::tetromino_to_physical(tetromino);
+        let mut phys_tetromino = Tetris::<T>::tetromino_to_physical(tetromino, rotation_system);
         match phys_tetromino.tetromino {
             Tetromino::O => {
-                phys_tetromino = phys_tetromino + Pos2::new(half_width as i32, TETRIS_FIELD_DEFAULT_HEIGHT as i32);
+                phys_tetromino = phys_tetromino + Pos2::new(half_width as i32, height);
             }
             Tetromino::Line | Tetromino::T | Tetromino::L | Tetromino::J | Tetromino::S | Tetromino::Z => {
-                phys_tetromino = phys_tetromino + Pos2::new((half_width - 1) as i32, TETRIS_FIELD_DEFAULT_HEIGHT as i32);
+                phys_tetromino = phys_tetromino + Pos2::new((half_width - 1) as i32, height);
             }
         }
 
-        if Tetris::<T>::try_move(field, &mut phys_tetromino, Direction::Down).is_ok()
-                && tetromino != Tetromino::Line {
-            let _ = Tetris::<T>::try_move(field, &mut phys_tetromino, Direction::Down);
+        let nbr_of_drops = if tetromino == Tetromino::Line {1} else {2};
+        for _ in 0..nbr_of_drops {
+            Tetris::<T>::try_move(field, &mut phys_tetromino, Direction::Down)
+                .map_err(|_| GameOver::BlockOut)?;
         }
 
-        phys_tetromino
+        Ok(phys_tetromino)
     }
 
-    /// Creates a new PhysicalTetromino with the correct color. 
-    fn tetromino_to_physical(tetromino: Tetromino) -> PhysicalTetromino {
+    /// Creates a new PhysicalTetromino with the correct color in the spawn orientation of the given 
+    /// rotation system. 
+    fn tetromino_to_physical(tetromino: Tetromino, rotation_system: RotationSystemKind) -> PhysicalTetromino {
+        let rotation_system = rotation_system.rotation_system();
         match tetromino {
             Tetromino::O => {
-                PhysicalTetromino::new(tetromino, CellStatus::Yellow)
+                PhysicalTetromino::new(tetromino, CellStatus::Yellow, rotation_system)
             }
             Tetromino::Line => {
-                PhysicalTetromino::new(tetromino, CellStatus::Cyan)
+                PhysicalTetromino::new(tetromino, CellStatus::Cyan, rotation_system)
             }
             Tetromino::T => {
-                PhysicalTetromino::new(tetromino, CellStatus::Purple)
+                PhysicalTetromino::new(tetromino, CellStatus::Purple, rotation_system)
             }
             Tetromino::L => {
-                PhysicalTetromino::new(tetromino, CellStatus::Orange)
+                PhysicalTetromino::new(tetromino, CellStatus::Orange, rotation_system)
             }
             Tetromino::J => {
-                PhysicalTetromino::new(tetromino, CellStatus::Blue)
+                PhysicalTetromino::new(tetromino, CellStatus::Blue, rotation_system)
             }
             Tetromino::S => {
-                PhysicalTetromino::new(tetromino, CellStatus::Green)
+                PhysicalTetromino::new(tetromino, CellStatus::Green, rotation_system)
             }
             Tetromino::Z => {
-                PhysicalTetromino::new(tetromino, CellStatus::Red)
+                PhysicalTetromino::new(tetromino, CellStatus::Red, rotation_system)
             }
         }
     }
@@ -240,245 +414,320 @@ impl<T: Rng + Sized + Send> Tetris<T> {
         self.ghost_piece = Tetris::<T>::find_ghost_piece_pos(&self.field, &self.active_piece);
     }
 
-    /// Takes the next piece and places it on the playfield. The now vacant next piece is assigned to
-    /// a randomly generated following piece. 
-    fn next_piece(&mut self) {
-        self.active_piece = Tetris::<T>::place_tetromino_on_field(&mut self.field, self.next_piece.tetromino);
-        self.next_piece = Tetris::<T>::tetromino_to_physical((&mut self.iterator).next().unwrap());
+    /// Takes the next piece out of the preview queue and places it on the playfield. Returns Err if the 
+    /// next piece could not be placed. 
+    fn next_piece(&mut self) -> Result<(), GameOver> {
+        let next = (&mut self.iterator).next().unwrap();
+        self.active_piece = Tetris::<T>::place_tetromino_on_field(&self.field, next, self.rules.rotation_system)?;
+        self.restart_lock_delay();
+        self.events.push(GameEvent::PieceSpawned(self.active_piece.tetromino));
+        Ok(())
     }
 
-    /// Checks if the active piece would collide with something during the attempted move, thereby 
-    /// preventing this move. 
-    fn check_move(field: &TetrisField, tetromino: &PhysicalTetromino, direction: Direction) -> Result<(), ()> {
-        let mut field_copy = *field;
-        let mut tetromino_copy = *tetromino;
-
-        //remove previous cells
-        for pos in tetromino_copy.coords {
-            if let Some(cell) = field_copy.get_mut(pos.x, pos.y) {
-                *cell = CellStatus::Empty;
-            }
+    /// Checks if the active piece rests on the stack or the floor. 
+    fn is_grounded(&self) -> bool {
+        Tetris::<T>::check_move(&self.field, &self.active_piece, Direction::Down).is_err()
+    }
+
+    /// Gives the active piece its full lock delay and all of its resets back. 
+    fn restart_lock_delay(&mut self) {
+        self.lock_timer = Duration::ZERO;
+        self.lock_resets = 0;
+        self.lowest_row = self.active_piece.lowest_row();
+    }
+
+    /// Restarts the running lock delay after the active piece was moved or spun, as far as the lock reset 
+    /// rule allows it. 
+    fn reset_lock_delay_after_move(&mut self) {
+        if self.lock_timer == Duration::ZERO {
+            //lock delay is not running, nothing to reset
+            return;
         }
 
-        //move active tetromino
-        match direction {
-            Direction::Down => {
-                tetromino_copy = (tetromino_copy - Pos2::new(0, 1))?;
-            }
-            Direction::Left => {
-                tetromino_copy = (tetromino_copy - Pos2::new(1, 0))?;
-            }
-            Direction::Right => {
-                tetromino_copy = tetromino_copy + Pos2::new(1, 0);
-                for pos in tetromino_copy.coords {
-                    if pos.x >= TETRIS_FIELD_DEFAULT_WIDTH as i32 {
-                        return Err(());
-                    }
+        match self.rules.lock_reset {
+            LockReset::Move => {
+                if self.lock_resets < LOCK_RESET_MOVE_LIMIT {
+                    self.lock_timer = Duration::ZERO;
+                    self.lock_resets += 1;
                 }
             }
-            Direction::Up => {
-                tetromino_copy = tetromino_copy + Pos2::new(0, 1);
+            LockReset::Infinite => {
+                self.lock_timer = Duration::ZERO;
             }
+            LockReset::Step => {}
         }
+    }
 
-        //assert each new position is empty and legal
-        for pos in tetromino_copy.coords {
-            if let Some(cell) = field_copy.get(pos.x, pos.y) {
-                if cell != CellStatus::Empty {
-                    return Err(())
-                }
-            }
+    /// Locks the active piece into the field where it currently is, clears all full lines and spawns the 
+    /// next piece. Returns Err with the reason if this ends the game. 
+    fn lock_active_piece(&mut self) -> Result<LockResult, GameOver> {
+        let result = self.try_lock_active_piece();
+        if let Err(reason) = result {
+            self.events.push(GameEvent::GameOver(reason));
         }
-
-        Ok(())
+        result
     }
 
-    /// Checks if the active piece can be moved in the indicated direction. Does so and Returns Ok if 
-    /// possible, does nothing and returns Err otherwise. 
-    fn try_move(field: &mut TetrisField, tetromino: &mut PhysicalTetromino, direction: Direction) -> Result<(), ()> {
-        //check if move is doable
-        Tetris::<T>::check_move(field, tetromino, direction)?;
-
-        //clear previous positions
-        for pos in tetromino.coords {
-            if let Some(cell) = field.get_mut(pos.x, pos.y) {
-                *cell = CellStatus::Empty;
-            }
+    /// Does the work of [Tetris::lock_active_piece] without recording the game over event. 
+    fn try_lock_active_piece(&mut self) -> Result<LockResult, GameOver> {
+        //check if pixel of any piece is outside of the playingfield
+        let cells_above = self.active_piece.coords
+            .iter()
+            .filter(|pos| pos.y >= self.field.height as i32)
+            .count();
+        if cells_above == self.active_piece.coords.len() {
+            return Err(GameOver::LockOut);
+        } else if cells_above > 0 {
+            return Err(GameOver::TopOut);
         }
 
-        //move tetromino coordinates
-        match direction {
-            Direction::Down => {
-                *tetromino = (*tetromino - Pos2::new(0, 1))?;
-            }
-            Direction::Left => {
-                *tetromino = (*tetromino - Pos2::new(1, 0))?;
-            }
-            Direction::Right => {
-                *tetromino = *tetromino + Pos2::new(1, 0);
-                for pos in tetromino.coords {
-                    if pos.x >= TETRIS_FIELD_DEFAULT_WIDTH as i32  {
-                        return Err(());
-                    }
-                }
-            }
-            Direction::Up => {
-                *tetromino = *tetromino + Pos2::new(0, 1);
-            }
+        //lock the piece into the field, do the usual cleanup and spawn new piece at the top
+        let t_spin = self.detect_t_spin();
+        self.field.lock(&self.active_piece);
+        self.events.push(GameEvent::Locked(self.active_piece.tetromino));
+        self.switchted_active_piece_since_last_drop = false;
+        self.last_rotation_kick = None;
+        let rows = self.check_for_lines_and_clear();
+        if !rows.is_empty() {
+            self.events.push(GameEvent::LinesCleared(rows.clone()));
         }
-
-        for pos in tetromino.coords {
-            if let Some(cell) = field.get_mut(pos.x, pos.y) {
-                *cell = tetromino.color;
-            }
+        let perfect_clear = !rows.is_empty() && self.field.field.iter().all(|cell| *cell == CellStatus::Empty);
+        let points = self.score.add_lock(self.rules.scoring, rows.len() as u32, t_spin, perfect_clear);
+
+        //the attack first cancels garbage waiting to be inserted, the rest is sent to the opponent
+        let attack = self.rules.attack.attack(rows.len() as u32, t_spin, perfect_clear);
+        let attack = self.cancel_pending_garbage(attack);
+        if attack > 0 {
+            self.events.push(GameEvent::GarbageSent(attack));
         }
+        self.apply_pending_garbage()?;
+        self.next_piece()?;
+        self.refresh_ghost_piece();
 
-        Ok(())
+        Ok(LockResult {
+            rows_cleared: rows.len() as u32,
+            rows,
+            t_spin,
+            perfect_clear,
+            points,
+        })
     }
 
-    /// Checks if any lines are completed and removes any full lines. Returns the number of cleared lines.
-    fn check_for_lines_and_clear(&mut self) -> u32 {
-        let mut counter = 0;
+    /// Checks if the active piece is a T which has been spun into its current position, using the 
+    /// 3-corner rule. Of the four cells diagonal to the center of the T, at least three have to be 
+    /// occupied, where the walls and floor count as occupied. If both corners in front of the pointing 
+    /// side of the T are occupied, or the spin needed the far SRS kick, it is a full T-spin, otherwise a 
+    /// T-spin mini. 
+    fn detect_t_spin(&self) -> TSpin {
+        let Some(kick) = self.last_rotation_kick else {return TSpin::None;};
+        if self.active_piece.tetromino != Tetromino::T {
+            return TSpin::None;
+        }
+
+        //the center cell is the one touching all three others
+        let coords = self.active_piece.coords;
+        let is_neighbor = |a: &Pos2, b: &Pos2| (a.x - b.x).abs() + (a.y - b.y).abs() == 1;
+        let Some(center) = coords.iter().find(|pos| coords.iter().filter(|other| is_neighbor(pos, other)).count() == 3) else {
+            return TSpin::None;
+        };
+
+        //the T points away from the only side of the center without a cell
+        let Some((back_x, back_y)) = [(0, 1), (1, 0), (0, -1), (-1, 0)]
+            .into_iter()
+            .find(|(x, y)| !coords.contains(&(*center + Pos2::new(*x, *y)))) else {
+            return TSpin::None;
+        };
+        let (front_x, front_y) = (-back_x, -back_y);
+
+        let is_occupied = |x: i32, y: i32| {
+            !(0..self.field.width as i32).contains(&x) 
+                || y < 0 
+                || self.field.get(x, y).is_some_and(|cell| cell != CellStatus::Empty)
+        };
+        //the corners are the center moved one cell forward or backward and one cell to either side
+        let count_corners = |(x, y): (i32, i32)| {
+            [(x + y, y + x), (x - y, y - x)]
+                .into_iter()
+                .filter(|(dx, dy)| is_occupied(center.x + dx, center.y + dy))
+                .count()
+        };
+        let front_corners = count_corners((front_x, front_y));
+        let back_corners = count_corners((back_x, back_y));
 
-        while let Some(line_index) = self.check_line_clearing() {
-            self.clear_line_and_drop_all_above(line_index);
-            counter += 1;
+        if front_corners + back_corners < 3 {
+            TSpin::None
+        } else if front_corners == 2 || (kick.0.abs() == 1 && kick.1.abs() == 2) {
+            TSpin::Full
+        } else {
+            TSpin::Mini
         }
+    }
+
+    /// Checks if the active piece would collide with something during the attempted move, thereby 
+    /// preventing this move. Returns the moved piece if the move is possible. 
+    fn check_move(field: &TetrisField, tetromino: &PhysicalTetromino, direction: Direction) -> Result<PhysicalTetromino, MoveError> {
+        let moved_tetromino = match direction {
+            Direction::Down => *tetromino + Pos2::new(0, -1),
+            Direction::Left => *tetromino + Pos2::new(-1, 0),
+            Direction::Right => *tetromino + Pos2::new(1, 0),
+        };
+
+        field.check_fit(&moved_tetromino)?;
 
-        counter
+        Ok(moved_tetromino)
     }
 
-    /// Iterates through the entire field, looking for any completed lines. Returns the height index
-    /// of the first encountered full line. 
-    fn check_line_clearing(&self) -> Option<u32> {
-        'outer: for y in 0..TETRIS_FIELD_DEFAULT_HEIGHT {
-            for x in 0..TETRIS_FIELD_DEFAULT_WIDTH {
-                if self.field.get(x as i32, y as i32).unwrap() == CellStatus::Empty {
-                    continue 'outer;
-                }
-            }
+    /// Checks if the active piece can be moved in the indicated direction. Does so and Returns Ok if 
+    /// possible, does nothing and returns Err otherwise. 
+    fn try_move(field: &TetrisField, tetromino: &mut PhysicalTetromino, direction: Direction) -> Result<(), MoveError> {
+        *tetromino = Tetris::<T>::check_move(field, tetromino, direction)?;
+
+        Ok(())
+    }
 
-            return Some(y);
+    /// Checks if any lines are completed and removes any full lines. Returns the height indices the 
+    /// cleared lines had before clearing, from bottom to top. 
+    fn check_for_lines_and_clear(&mut self) -> Vec<u32> {
+        let full_lines: Vec<u32> = (0..self.field.height)
+            .filter(|y| self.is_line_full(*y))
+            .collect();
+
+        //clear from the top so the indices of the lower lines stay valid
+        for line_index in full_lines.iter().rev() {
+            self.clear_line_and_drop_all_above(*line_index);
         }
 
-        None
+        full_lines
+    }
+
+    /// Checks if every cell of the line at the given height index is occupied. 
+    fn is_line_full(&self, y: u32) -> bool {
+        (0..self.field.width).all(|x| self.field.get(x as i32, y as i32).unwrap() != CellStatus::Empty)
     }
 
     /// Clears the line at the given height index and drops every cell above that by one. 
     fn clear_line_and_drop_all_above(&mut self, line: u32) {
+        let width = self.field.width as usize;
+        let height = self.field.height as usize;
         let field = &mut self.field.field;
-        for line in (line as usize)..(TETRIS_FIELD_DEFAULT_HEIGHT as usize - 1) {
-            let start_index = line * TETRIS_FIELD_DEFAULT_WIDTH as usize;
-            let mid_index = start_index + TETRIS_FIELD_DEFAULT_WIDTH as usize;
-            let end_index = mid_index + TETRIS_FIELD_DEFAULT_WIDTH as usize;
+        for line in (line as usize)..(height - 1) {
+            let start_index = line * width;
+            let mid_index = start_index + width;
+            let end_index = mid_index + width;
 
             field.copy_within(mid_index..end_index, start_index);
         }
 
-        let last_line_index = (TETRIS_FIELD_DEFAULT_WIDTH * (TETRIS_FIELD_DEFAULT_HEIGHT - 1)) as usize;
-        field[last_line_index..].copy_from_slice(&[CellStatus::Empty; TETRIS_FIELD_DEFAULT_WIDTH as usize]);
+        let last_line_index = width * (height - 1);
+        field[last_line_index..].fill(CellStatus::Empty);
     }
 
-    /// Tries to spin the active piece in the indicated direction. Returns Err if spinning was not 
-    /// possible. 
-    fn try_spin(&mut self, spin_direction: SpinDirection) -> Result<(), ()> {
-        if self.check_spin(spin_direction).is_err() {
-            let tetromino_original = self.active_piece;
-            let field_original = self.field;
-            let tetromino_copy = tetromino_original;
-            let field_copy = field_original;
-            self.active_piece = tetromino_copy;
-            self.field = field_copy;
-
-            Tetris::<T>::try_move(&mut self.field, &mut self.active_piece, Direction::Up)?;
-
-            if self.check_spin(spin_direction).is_err() {
-                self.active_piece = tetromino_original;
-                self.field = field_original;
-                return Err(())
+    /// Removes up to the given number of queued garbage rows, oldest first. Returns how many of the 
+    /// given rows were left over. 
+    fn cancel_pending_garbage(&mut self, mut rows: u32) -> u32 {
+        while rows > 0 && let Some(garbage) = self.pending_garbage.front_mut() {
+            let cancelled = garbage.rows.min(rows);
+            garbage.rows -= cancelled;
+            rows -= cancelled;
+            if garbage.rows == 0 {
+                self.pending_garbage.pop_front();
             }
         }
 
-        //remove previous blocks
-        for pos in self.active_piece.coords {
-            if let Some(cell) = self.field.get_mut(pos.x, pos.y) {
-                *cell = CellStatus::Empty;
-            }
+        rows
+    }
+
+    /// Inserts all queued garbage rows at the bottom of the field. Returns Err if this pushed locked cells 
+    /// past the top of the field. 
+    fn apply_pending_garbage(&mut self) -> Result<(), GameOver> {
+        while let Some(garbage) = self.pending_garbage.pop_front() {
+            self.insert_garbage(garbage)?;
         }
 
-        //spin
-        self.active_piece.spin(spin_direction);
+        Ok(())
+    }
 
-        //add new blocks 
-        for pos in self.active_piece.coords {
-            if let Some(cell) = self.field.get_mut(pos.x, pos.y) {
-                *cell = self.active_piece.color;
-            }
+    /// Pushes the stack up and fills the freed lines at the bottom with garbage. Garbage is only inserted 
+    /// right after the active piece has been locked, or at the start for the cheese rows, so the active 
+    /// piece is left where it is. Returns Err if locked cells were pushed past the top of the field, 
+    /// these cells are lost. 
+    fn insert_garbage(&mut self, garbage: GarbageRows) -> Result<(), GameOver> {
+        let width = self.field.width as usize;
+        let rows = garbage.rows.min(self.field.height) as usize;
+        if rows == 0 {
+            return Ok(());
+        }
+
+        //everything in the top lines ends up above the field
+        let field = &mut self.field.field;
+        let kept_cells = field.len() - rows * width;
+        let topped_out = field[kept_cells..].iter().any(|cell| *cell != CellStatus::Empty);
+
+        //move the stack up and fill the bottom lines
+        field.copy_within(0..kept_cells, rows * width);
+        for line in field[..rows * width].chunks_mut(width) {
+            line.fill(CellStatus::Garbage);
+            line[garbage.hole_column as usize] = CellStatus::Empty;
         }
+        self.events.push(GameEvent::GarbageInserted(rows as u32));
 
+        if topped_out {
+            return Err(GameOver::TopOut);
+        }
+        Ok(())
+    }
+
+    /// Tries to spin the active piece in the indicated direction. Returns Err if spinning was not 
+    /// possible. 
+    fn try_spin(&mut self, spin_direction: SpinDirection) -> Result<(), MoveError> {
+        let (spun_piece, kick) = self.check_spin(spin_direction)?;
+        self.active_piece = spun_piece;
+        self.last_rotation_kick = Some(kick);
+        self.reset_lock_delay_after_move();
         self.refresh_ghost_piece();
+        self.events.push(GameEvent::Rotated { direction: spin_direction, kick });
 
         Ok(())
     }
 
     /// Checks if the active piece could be spun in the indicated direction without colliding with 
-    /// anything else. Returns Ok if spin is possible, Err otherwise. 
-    fn check_spin(&self, spin_direction: SpinDirection) -> Result<(), ()> {
-        let mut field_copy = self.field;
+    /// anything else. Every wall kick of the rotation system is tested in order and the first one 
+    /// that fits is applied. Returns the spun and kicked piece along with the applied kick if the spin is 
+    /// possible, Err with the reason the last kick failed otherwise. 
+    fn check_spin(&self, spin_direction: SpinDirection) -> Result<(PhysicalTetromino, (i32, i32)), MoveError> {
+        let rotation_system = self.rules.rotation_system.rotation_system();
         let mut tetromino_copy = self.active_piece;
 
-        //clear previous position
-        for pos in tetromino_copy.coords {
-            if let Some(cell) = field_copy.get_mut(pos.x, pos.y) {
-                *cell = CellStatus::Empty;
-            }
-        }
-
         //spin the active piece
-        tetromino_copy.spin(spin_direction);
+        let from = tetromino_copy.rotation;
+        rotation_system.spin(&mut tetromino_copy, spin_direction);
+        let to = tetromino_copy.rotation;
+
+        //try every kick in order
+        let mut error = MoveError::BlockedByStack;
+        for (index, kick) in rotation_system.kicks(tetromino_copy.tetromino, from, to).iter().enumerate() {
+            if index > 0 && rotation_system.prevents_kicks(&tetromino_copy, &self.field) {
+                break;
+            }
+            let kicked_tetromino = tetromino_copy + Pos2::new(kick.0, kick.1);
 
-        //check the new positions
-        for pos in tetromino_copy.coords {
-            if let Some(cell) = field_copy.get(pos.x, pos.y) {
-                if !(0..(TETRIS_FIELD_DEFAULT_WIDTH as i32)).contains(&pos.x) || !(cell == CellStatus::Empty || cell == tetromino_copy.color) {
-                    return Err(());
-                }
-            } else {
-                return Err(());
+            match self.field.check_fit(&kicked_tetromino) {
+                Ok(_) => return Ok((kicked_tetromino, *kick)),
+                Err(err) => error = err,
             }
         }
 
-        Ok(())
+        Err(error)
     }
 
     /// Returns the position where the ghost piece should be. 
     fn find_ghost_piece_pos(field: &TetrisField, tetromino: &PhysicalTetromino) -> PhysicalTetromino {
-        let mut field = *field;
-        
-        for pos in tetromino.coords {
-            if let Some(cell) = field.get_mut(pos.x, pos.y) {
-                *cell = CellStatus::Empty;
-            }
-        }
-
-        let mut y = 0;
-        'outer: loop {
-            let lower_tetromino = *tetromino + Pos2::new(0, y - 1);
-
-            for pos in lower_tetromino.coords {
-                if let Some(cell) = field.get(pos.x, pos.y) {
-                    if cell != CellStatus::Empty {
-                        break 'outer;
-                    }
-                } else {
-                    break 'outer;
-                }
-            }
-            y -= 1;
+        let mut return_val = *tetromino;
+        while field.check_fit(&(return_val + Pos2::new(0, -1))).is_ok() {
+            return_val = return_val + Pos2::new(0, -1);
         }
 
-        let mut return_val = PhysicalTetromino::new(tetromino.tetromino, CellStatus::Empty);
-        return_val.coords = (*tetromino + Pos2::new(0, y)).coords;
+        return_val.color = CellStatus::Empty;
         return_val
     }
 }
@@ -486,50 +735,287 @@ impl<T: Rng + Sized + Send> Tetris<T> {
 impl Default for Tetris<rand::rngs::OsRng> {
     fn default() -> Self {
         let rng = rand::rngs::OsRng;
-        Tetris::new(rng)
+        Tetris::new(rng, TetrisRules::default())
+    }
+}
+
+/// The rules a [Tetris] game is played with. They are fixed for the entire game. 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TetrisRules {
+    pub rotation_system: RotationSystemKind,
+    pub field_width: u32,
+    pub field_height: u32,
+    pub scoring: ScoringRules,
+    /// How long a piece resting on the ground may still be moved before it locks. With no lock delay, 
+    /// a grounded piece locks with the next drop. 
+    pub lock_delay_millis: u32,
+    pub lock_reset: LockReset,
+    pub randomizer: RandomizerKind,
+    /// The number of garbage rows the field starts with, at most half of the field height. 
+    pub cheese_rows: u32,
+    pub attack: AttackTable,
+    pub hold: HoldRule,
+}
+
+impl Default for TetrisRules {
+    fn default() -> Self {
+        Self {
+            rotation_system: RotationSystemKind::default(),
+            field_width: TETRIS_FIELD_DEFAULT_WIDTH,
+            field_height: TETRIS_FIELD_DEFAULT_HEIGHT,
+            scoring: ScoringRules::default(),
+            lock_delay_millis: LOCK_DELAY_DEFAULT_MILLIS,
+            lock_reset: LockReset::default(),
+            randomizer: RandomizerKind::default(),
+            cheese_rows: 0,
+            attack: AttackTable::default(),
+            hold: HoldRule::default(),
+        }
     }
 }
 
-/// The Tetris field of the Tetris struct. It is simply a wrapper struct for an array with the 
-/// appropriate get functions. 
-#[derive(Clone, Copy, Debug)]
+/// The rules for restarting the lock delay of a grounded piece. Reaching a row lower than ever before 
+/// always restarts it. 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LockReset {
+    /// Moving or spinning the piece restarts the lock delay, at most [LOCK_RESET_MOVE_LIMIT] times. 
+    /// Once the resets are used up, the piece locks as soon as it touches the ground. 
+    #[default]
+    Move,
+    /// Moving or spinning the piece always restarts the lock delay. 
+    Infinite,
+    /// Only reaching a lower row restarts the lock delay. 
+    Step,
+}
+
+impl LockReset {
+    /// Returns an array of all lock reset rules. 
+    pub fn all_lock_reset_array() -> [Self; 3] {
+        [
+            Self::Move,
+            Self::Infinite,
+            Self::Step,
+        ]
+    }
+}
+
+/// The rules for switching the active piece with the stored piece. 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HoldRule {
+    /// The active piece can be held once, afterwards a piece has to be locked before holding again. 
+    #[default]
+    Once,
+    /// The active piece can be switched with the stored piece any number of times. 
+    Unlimited,
+    /// Holding is not possible at all. 
+    Disabled,
+}
+
+impl HoldRule {
+    /// Returns an array of all hold rules. 
+    pub fn all_hold_rule_array() -> [Self; 3] {
+        [
+            Self::Once,
+            Self::Unlimited,
+            Self::Disabled,
+        ]
+    }
+}
+
+/// The Tetris field of the Tetris struct. It is simply a wrapper struct for a heap allocated list of 
+/// cells, row by row starting at the bottom, with the appropriate get functions. 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct TetrisField {
-    field: [CellStatus; TETRIS_FIELD_LENGTH],
+    field: Vec<CellStatus>,
+    width: u32,
+    height: u32,
 }
 
 impl TetrisField {
+    /// Creates an empty field with the given dimensions. 
+    fn new(width: u32, height: u32) -> Self {
+        Self {
+            field: vec![CellStatus::Empty; (width * height) as usize],
+            width,
+            height,
+        }
+    }
+
     /// Returns the copied cellstatus at the given coordinates. Returns None if coordinates are 
     /// out of bounds. 
     fn get(&self, x: i32, y: i32) -> Option<CellStatus> {
-        if !(0..TETRIS_FIELD_DEFAULT_WIDTH as i32).contains(&x) || !(0..TETRIS_FIELD_DEFAULT_HEIGHT as i32).contains(&y) {
+        if !(0..self.width as i32).contains(&x) || !(0..self.height as i32).contains(&y) {
             None
         } else {
-            Some(self.field[(y * TETRIS_FIELD_DEFAULT_WIDTH as i32 + x) as usize])
+            Some(self.field[(y * self.width as i32 + x) as usize])
         }
     }
 
     /// Returns a mutable reference to the cellstatus at the given coordinates. Returns None if the
     /// coordinates are out of bounds. 
     fn get_mut(&mut self, x: i32, y: i32) -> Option<&mut CellStatus> {
-        if !(0..TETRIS_FIELD_DEFAULT_WIDTH as i32).contains(&x) || !(0..TETRIS_FIELD_DEFAULT_HEIGHT as i32).contains(&y) {
+        if !(0..self.width as i32).contains(&x) || !(0..self.height as i32).contains(&y) {
             None
         } else {
-            Some(&mut self.field[(y * TETRIS_FIELD_DEFAULT_WIDTH as i32 + x) as usize])
+            Some(&mut self.field[(y * self.width as i32 + x) as usize])
+        }
+    }
+
+    /// Checks if the tetromino could be placed at its position without overlapping any locked cell or 
+    /// leaving the field to the left, right or bottom. Cells above the top of the field are allowed. 
+    /// Returns Err with what is in the way otherwise. 
+    fn check_fit(&self, tetromino: &PhysicalTetromino) -> Result<(), MoveError> {
+        for pos in tetromino.coords {
+            if !(0..self.width as i32).contains(&pos.x) || pos.y < 0 {
+                return Err(MoveError::BlockedByWall);
+            }
+        }
+
+        for pos in tetromino.coords {
+            if self.get(pos.x, pos.y).is_some_and(|cell| cell != CellStatus::Empty) {
+                return Err(MoveError::BlockedByStack);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if the cell lies to the left, right or below the field or is already taken. 
+    fn is_blocked(&self, pos: Pos2) -> bool {
+        !(0..self.width as i32).contains(&pos.x) || pos.y < 0 || self.get(pos.x, pos.y).is_some_and(|cell| cell != CellStatus::Empty)
+    }
+
+    /// Writes the cells of the tetromino into the field. Cells above the top of the field are lost. 
+    fn lock(&mut self, tetromino: &PhysicalTetromino) {
+        for pos in tetromino.coords {
+            if let Some(cell) = self.get_mut(pos.x, pos.y) {
+                *cell = tetromino.color;
+            }
         }
     }
 }
 
-impl From<[CellStatus; TETRIS_FIELD_LENGTH]> for TetrisField {
-    fn from(value: [CellStatus; TETRIS_FIELD_LENGTH]) -> Self {
-        Self {
-            field: value,
+/// The reasons a move, spin or hold of the active piece can be refused. 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveError {
+    /// The piece would leave the field to the left, right or bottom. 
+    BlockedByWall,
+    /// The piece would overlap locked cells. 
+    BlockedByStack,
+    /// The active piece has already been switched with the stored piece since the last lock. 
+    HoldAlreadyUsed,
+    /// The rules do not allow holding. 
+    HoldDisabled,
+}
+
+impl std::fmt::Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BlockedByWall => write!(f, "blocked by wall"),
+            Self::BlockedByStack => write!(f, "blocked by stack"),
+            Self::HoldAlreadyUsed => write!(f, "hold already used"),
+            Self::HoldDisabled => write!(f, "hold disabled"),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// The reasons a game can end. 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(clippy::enum_variant_names)]
+pub enum GameOver {
+    /// A new piece could not be spawned because its spawn position is blocked by the stack. 
+    BlockOut,
+    /// A piece was locked entirely above the top of the field. 
+    LockOut,
+    /// A piece was locked partially above the top of the field. 
+    TopOut,
+    /// The player gave up. 
+    Forfeit,
+}
+
+impl std::fmt::Display for GameOver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BlockOut => write!(f, "block out"),
+            Self::LockOut => write!(f, "lock out"),
+            Self::TopOut => write!(f, "top out"),
+            Self::Forfeit => write!(f, "forfeit"),
         }
     }
 }
 
+impl std::error::Error for GameOver {}
+
+/// The outcome of a successfull drop by one cell. 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DropOutcome {
+    /// The piece moved down by one cell. 
+    Moved,
+    /// The piece is already at the bottom and waits for its lock delay to pass. 
+    Grounded,
+    /// The piece was already at the bottom and has been locked into the field. 
+    Locked(LockResult),
+}
+
+/// The result of locking a piece into the field. 
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LockResult {
+    /// The number of lines cleared by the lock. 
+    pub rows_cleared: u32,
+    /// The height indices the cleared lines had before clearing, from bottom to top. 
+    pub rows: Vec<u32>,
+    /// Whether the piece was spun into place right before locking. 
+    pub t_spin: TSpin,
+    /// Whether the lock cleared the entire field. 
+    pub perfect_clear: bool,
+    /// The points awarded for the lock. 
+    pub points: u32,
+}
+
+/// The outcome of a successfull drop all the way down. 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HardDropOutcome {
+    /// The number of cells the piece fell before locking. 
+    pub cells_dropped: u32,
+    /// The result of locking the piece. 
+    pub lock_result: LockResult,
+}
+
+/// Something that happened during a [Tetris] game. The events are recorded in the order they happened 
+/// and can be taken out with [Tetris::drain_events]. 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GameEvent {
+    /// A new active piece has been placed on top of the field. 
+    PieceSpawned(Tetromino),
+    /// The active piece moved by one cell in the given direction. 
+    Moved(Direction),
+    /// The active piece was spun in the given direction and moved by the kick that made it fit. 
+    Rotated {
+        direction: SpinDirection,
+        kick: (i32, i32),
+    },
+    /// The active piece fell the given number of cells straight down and is about to lock. 
+    HardDropped(u32),
+    /// The active piece has been locked into the field. 
+    Locked(Tetromino),
+    /// The lines with the given height indices, counted before clearing from bottom to top, were cleared. 
+    LinesCleared(Vec<u32>),
+    /// The given active piece was switched into storage. 
+    Held(Tetromino),
+    /// The given number of garbage rows were inserted at the bottom of the field. 
+    GarbageInserted(u32),
+    /// A lock cleared lines worth the given number of garbage rows for the opponent. 
+    GarbageSent(u32),
+    /// The game has ended for the given reason. 
+    GameOver(GameOver),
+}
+
 /// An enum describing the states a cell can have, simply unoccupied or occupied by a color indicating
 /// a particular tetromino. 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum CellStatus {
     Empty,
     Cyan,
@@ -539,11 +1025,12 @@ pub enum CellStatus {
     Red, 
     Blue, 
     Orange,
+    Garbage,
 }
 
 /// An enum listing the 7 different Tetrominos. 
-#[derive(Copy, Clone, Debug, PartialEq)]
-enum Tetromino {
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Tetromino {
     Line,
     O,
     L, 
@@ -566,36 +1053,57 @@ impl Tetromino {
             Self::T,
         ]
     }
+
+    /// Returns the Tetromino with the given letter, ignoring case. The Line is the I. Returns None for 
+    /// any other character. 
+    pub fn from_letter(letter: char) -> Option<Self> {
+        match letter.to_ascii_uppercase() {
+            'I' => Some(Self::Line),
+            'O' => Some(Self::O),
+            'L' => Some(Self::L),
+            'J' => Some(Self::J),
+            'Z' => Some(Self::Z),
+            'S' => Some(Self::S),
+            'T' => Some(Self::T),
+            _ => None,
+        }
+    }
 }
 
-/// An infinite Iterator which returns a random Tetromino when prompted. The random method used 
-/// is the classic Tetris random: <br/>
-/// Take a list of 7 distinct Tetrominos, shuffle them and then return the list in order. When 
-/// all 7 have been dealt out, get a new list of tetrominos and repeat. This guarantees a repeat of
-/// the same tetromino after at most 12 others.
-#[derive(Clone, Debug)]
+/// An infinite Iterator which returns the Tetrominos dealt by its [Randomizer] when prompted. The next 
+/// [MAX_PREVIEW_COUNT] tetrominos are always generated in advance and can be peeked at. 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct TetrominoIterator<T: Rng + Sized> {
-    pieces: Vec<Tetromino>,
+    randomizer: Box<dyn Randomizer>,
+    queue: VecDeque<Tetromino>,
     rng: T,
 }
 
 impl<T: Rng + Sized> TetrominoIterator<T> {
     /// Creates a new instance with the given random number generator, allowing for a fixed progression
-    /// of pieces. 
-    pub fn new(mut rng: T) -> Self {
-        let pieces = Vec::from(Self::get_new_seven(&mut rng));
-
-        Self {
-            pieces,
+    /// of pieces, and the randomizer deciding which pieces are dealt. 
+    pub fn new(rng: T, randomizer: Box<dyn Randomizer>) -> Self {
+        let mut iterator = Self {
+            randomizer,
+            queue: VecDeque::with_capacity(MAX_PREVIEW_COUNT as usize),
             rng,
+        };
+        while iterator.queue.len() < MAX_PREVIEW_COUNT as usize {
+            let piece = iterator.generate();
+            iterator.queue.push_back(piece);
         }
+
+        iterator
     }
 
-    /// Returns a shuffled list of 7 tetrominos. 
-    fn get_new_seven(rng: &mut T) -> [Tetromino; NBR_OF_TETROMINUS as usize] {
-        let mut pieces = Tetromino::all_tetromino_array();
-        pieces.shuffle(rng);
-        pieces
+    /// Returns the upcoming tetrominos in the order they will be returned, without taking them. 
+    pub fn peek(&self) -> impl Iterator<Item = &Tetromino> {
+        self.queue.iter()
+    }
+
+    /// Generates the tetromino following the last one in the queue. 
+    fn generate(&mut self) -> Tetromino {
+        self.randomizer.next_tetromino(&mut self.rng)
     }
 }
 
@@ -603,18 +1111,14 @@ impl<T: Rng + Sized> Iterator for &mut TetrominoIterator<T> {
     type Item = Tetromino;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.pieces.is_empty() {
-            let new = TetrominoIterator::get_new_seven(&mut self.rng);
-            self.pieces.extend_from_slice(&new);
-        }
-
-        let len = self.pieces.len();
-        Some(self.pieces.remove(len - 1))
+        let piece = self.generate();
+        self.queue.push_back(piece);
+        self.queue.pop_front()
     }
 }
 
 /// A struct containing a 2d integer position. 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 struct Pos2 {
     pub x: i32, 
     pub y: i32,
@@ -641,20 +1145,6 @@ impl std::ops::Add<Pos2> for Pos2 {
     }
 }
 
-impl std::ops::Sub<Pos2> for Pos2 {
-    type Output = Result<Pos2, ()>;
-
-    fn sub(mut self, rhs: Pos2) -> Self::Output {
-        if self.x >= rhs.x && self.y >= rhs.y {
-            self.x -= rhs.x;
-            self.y -= rhs.y;
-            Ok(self)
-        } else {
-            Err(())
-        }
-    }
-}
-
 impl From<Pos2f> for Pos2 {
     fn from(value: Pos2f) -> Self {
         Pos2 {
@@ -664,8 +1154,15 @@ impl From<Pos2f> for Pos2 {
     }
 }
 
+/// A number of garbage rows waiting to be inserted, all with their hole in the same column. 
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct GarbageRows {
+    rows: u32,
+    hole_column: u32,
+}
+
 /// A struct containing a 2d floating point position. 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 struct Pos2f {
     x: f32,
     y: f32,
@@ -696,6 +1193,12 @@ impl Pos2f {
         self.x = -y;
         self.y = x;
     }
+
+    /// Rotates the point 180° with respect to (0, 0).
+    fn rotate_180(&mut self) {
+        self.x = -self.x;
+        self.y = -self.y;
+    }
 }
 
 impl From<Pos2> for Pos2f {
@@ -729,12 +1232,13 @@ impl std::ops::Sub<Pos2f> for Pos2f {
     }
 }
 
-/// A tetromino which contains information about the color and position of individual cells as well as the 
-/// point around which the individual cells will be rotated. 
-#[derive(Clone, Copy, Debug)]
+/// A tetromino which contains information about the color and position of individual cells, the 
+/// point around which the individual cells will be rotated and its current rotation state. 
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 struct PhysicalTetromino {
     coords: [Pos2; 4],
     rotation_center: Pos2f,
+    rotation: RotationState,
     tetromino: Tetromino,
     color: CellStatus,
 }
@@ -742,97 +1246,23 @@ struct PhysicalTetromino {
 impl PhysicalTetromino {
     /// Generates a new Physical Tetromino from a given [Tetromino]. The initial position of a physical 
     /// Tetromino will be (0, 0) for the left-most, lowest cube - iff the shape is konvex at the bottom left. 
-    fn new(tetromino: Tetromino, color: CellStatus) -> Self {
-        let (coords, rotation_center) = match tetromino {
-            Tetromino::Line => {
-                (
-                    [
-                        Pos2::new(0, 0),
-                        Pos2::new(1, 0),
-                        Pos2::new(2, 0),
-                        Pos2::new(3, 0),
-                    ],
-                    Pos2f::new(1.5, 0.0),
-                )
-            }
-            Tetromino::O => {
-                (   
-                    [
-                        Pos2::new(0, 0),
-                        Pos2::new(0, 1),
-                        Pos2::new(1, 0),
-                        Pos2::new(1, 1),
-                    ],
-                    Pos2f::new(0.5, 0.5),
-                )
-            }
-            Tetromino::J => {
-                ( 
-                    [
-                        Pos2::new(0, 0),
-                        Pos2::new(0, 1),
-                        Pos2::new(1, 0),
-                        Pos2::new(2, 0),
-                    ],
-                    Pos2f::new(1.0, 0.5),
-                )
-            }
-            Tetromino::L => {
-                (
-                    [
-                        Pos2::new(0, 0),
-                        Pos2::new(1, 0),
-                        Pos2::new(2, 0),
-                        Pos2::new(2, 1),
-                    ],
-                    Pos2f::new(1.0, 0.5),
-                )
-            }
-            Tetromino::S => {
-                (   
-                    [
-                        Pos2::new(0, 0),
-                        Pos2::new(1, 0),
-                        Pos2::new(1, 1),
-                        Pos2::new(2, 1),
-                    ], 
-                    Pos2f::new(1.0, 0.5),
-                )
-            }
-            Tetromino::Z => {
-                (
-                    [
-                        Pos2::new(0, 1),
-                        Pos2::new(1, 1),
-                        Pos2::new(1, 0),
-                        Pos2::new(2, 0),
-                    ],
-                    Pos2f::new(1.0, 0.5),
-                )
-            }
-            Tetromino::T => {
-                (
-                    [
-                        Pos2::new(0, 0),
-                        Pos2::new(1, 0),
-                        Pos2::new(2, 0),
-                        Pos2::new(1, 1),
-                    ],
-                    Pos2f::new(1.0, 0.0),
-                )
-            }
-        };
+    /// The cells are in the spawn orientation of the given rotation system. 
+    fn new(tetromino: Tetromino, color: CellStatus, rotation_system: &dyn RotationSystem) -> Self {
+        let (coords, rotation_center) = rotation_system.spawn_shape(tetromino);
 
         Self {
             coords, 
             rotation_center,
+            rotation: RotationState::Spawn,
             tetromino,
             color,
         }
     }
 
-    /// Spins the piece in the given direction. 
+    /// Spins the piece in the given direction around its rotation center and updates its rotation state. 
     fn spin(&mut self, spin_direction: SpinDirection) {
+        self.rotation = self.rotation.spin(spin_direction);
+
         for pos in &mut self.coords {
             let mut float_pos = Pos2f::from(*pos);
 
@@ -847,6 +1277,9 @@ impl PhysicalTetromino {
                 SpinDirection::CounterClockwise => {
                     float_pos.rotate_counter_90();
                 }
+                SpinDirection::Half => {
+                    float_pos.rotate_180();
+                }
             }
 
             //retranslate
@@ -855,6 +1288,11 @@ impl PhysicalTetromino {
             *pos = Pos2::from(float_pos);
         }
     }
+
+    /// Returns the height index of the lowest cell of the piece. 
+    fn lowest_row(&self) -> i32 {
+        self.coords.iter().map(|pos| pos.y).min().unwrap_or(0)
+    }
 }
 
 impl std::ops::Add<Pos2> for PhysicalTetromino {
@@ -871,32 +1309,698 @@ impl std::ops::Add<Pos2> for PhysicalTetromino {
     }
 }
 
-impl std::ops::Sub<Pos2> for PhysicalTetromino {
-    type Output = Result<PhysicalTetromino, ()>;
+/// The three directions a tetromino can be moved in. 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Left, 
+    Right, 
+    Down,
+}
+
+/// A spin direction on a 2d plane. 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpinDirection {
+    Clockwise, 
+    CounterClockwise,
+    /// A half turn, which ends up in the same state either way round. 
+    Half,
+}
 
-    fn sub(mut self, rhs: Pos2) -> Self::Output {
-        for pos in &mut self.coords {
-            *pos = (*pos - rhs)?;
+/// The four rotation states of a tetromino as named by the Super Rotation System: the spawn state 
+/// (0), one clockwise spin from spawn (R), two spins from spawn (2) and one counterclockwise spin 
+/// from spawn (L). 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum RotationState {
+    Spawn,
+    Right,
+    Two,
+    Left,
+}
+
+impl RotationState {
+    /// Returns the rotation state after spinning in the given direction. 
+    fn spin(self, spin_direction: SpinDirection) -> Self {
+        match (self, spin_direction) {
+            (Self::Spawn, SpinDirection::Clockwise) => Self::Right,
+            (Self::Right, SpinDirection::Clockwise) => Self::Two,
+            (Self::Two, SpinDirection::Clockwise) => Self::Left,
+            (Self::Left, SpinDirection::Clockwise) => Self::Spawn,
+            (Self::Spawn, SpinDirection::CounterClockwise) => Self::Left,
+            (Self::Left, SpinDirection::CounterClockwise) => Self::Two,
+            (Self::Two, SpinDirection::CounterClockwise) => Self::Right,
+            (Self::Right, SpinDirection::CounterClockwise) => Self::Spawn,
+            (Self::Spawn, SpinDirection::Half) => Self::Two,
+            (Self::Right, SpinDirection::Half) => Self::Left,
+            (Self::Two, SpinDirection::Half) => Self::Spawn,
+            (Self::Left, SpinDirection::Half) => Self::Right,
         }
+    }
+}
 
-        self.rotation_center = self.rotation_center - rhs.into();
+/// The rotation systems a [Tetris] game can be played with. Each one determines the spawn orientation 
+/// of the tetrominos, how they rotate and which wall kicks are tried when a rotation is blocked. 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RotationSystemKind {
+    /// The Super Rotation System of the Tetris guideline. 
+    #[default]
+    Srs,
+    /// The Arika Rotation System of the TGM series. 
+    Ars,
+    /// The Nintendo Rotation System of the NES and Game Boy games. 
+    Nrs,
+    /// The rotation system this game originally shipped with. 
+    Classic,
+}
 
-        Ok(self)
+impl RotationSystemKind {
+    /// Returns an array of all rotation systems. 
+    pub fn all_rotation_system_array() -> [Self; 4] {
+        [
+            Self::Srs,
+            Self::Ars,
+            Self::Nrs,
+            Self::Classic,
+        ]
+    }
+
+    /// Returns the implementation of this rotation system. 
+    fn rotation_system(self) -> &'static dyn RotationSystem {
+        match self {
+            Self::Srs => &SuperRotationSystem,
+            Self::Ars => &ArikaRotationSystem,
+            Self::Nrs => &NintendoRotationSystem,
+            Self::Classic => &ClassicRotationSystem,
+        }
     }
 }
 
-/// The four cardinal directions
-#[derive(Clone, Copy, Debug)]
-enum Direction {
-    Left, 
-    Right, 
-    Down,
-    Up,
+/// A rotation system supplies the spawn shapes of the tetrominos, rotates them and lists the wall 
+/// kicks to try when a rotation is blocked. 
+trait RotationSystem {
+    /// Returns the cells of the given tetromino in spawn orientation along with its rotation center. 
+    /// The lowest, left-most cell should be at (0, 0). 
+    fn spawn_shape(&self, tetromino: Tetromino) -> ([Pos2; 4], Pos2f);
+
+    /// Spins the tetromino in the given direction, without checking for collisions. Defaults to a 
+    /// rotation around the rotation center of the tetromino. 
+    fn spin(&self, tetromino: &mut PhysicalTetromino, spin_direction: SpinDirection) {
+        tetromino.spin(spin_direction);
+    }
+
+    /// Returns the offsets to be tested in order when rotating from one rotation state to another. 
+    /// The first offset that fits is used. 
+    fn kicks(&self, tetromino: Tetromino, from: RotationState, to: RotationState) -> &'static [(i32, i32)];
+
+    /// Returns true if the given spun tetromino, which does not fit on the field unkicked, must not be 
+    /// kicked either. Defaults to false. 
+    fn prevents_kicks(&self, _spun: &PhysicalTetromino, _field: &TetrisField) -> bool {
+        false
+    }
 }
 
-/// A spin direction on a 2d plane. 
-#[derive(Clone, Copy, Debug)]
-enum SpinDirection {
-    Clockwise, 
-    CounterClockwise,
+/// The Super Rotation System of the Tetris guideline. Tetrominos rotate around the center of their 
+/// bounding box and every rotation tests up to five wall kicks. The I piece has its own kick table 
+/// and the O piece never kicks. Half turns are not part of the guideline and use the six kicks of 
+/// the 180° table popularized by TETR.IO for every piece but the O. 
+struct SuperRotationSystem;
+
+impl SuperRotationSystem {
+    /// Returns the kicks to test for a half turn between the given rotation states. 
+    fn half_turn_kicks(from: RotationState, to: RotationState) -> &'static [(i32, i32)] {
+        use RotationState::*;
+
+        match (from, to) {
+            (Spawn, Two) => &[(0, 0), (0, 1), (1, 1), (-1, 1), (1, 0), (-1, 0)],
+            (Right, Left) => &[(0, 0), (1, 0), (1, 2), (1, 1), (0, 2), (0, 1)],
+            (Two, Spawn) => &[(0, 0), (0, -1), (-1, -1), (1, -1), (-1, 0), (1, 0)],
+            (Left, Right) => &[(0, 0), (-1, 0), (-1, 2), (-1, 1), (0, 2), (0, 1)],
+            _ => &[(0, 0)],
+        }
+    }
+}
+
+impl RotationSystem for SuperRotationSystem {
+    fn spawn_shape(&self, tetromino: Tetromino) -> ([Pos2; 4], Pos2f) {
+        let rotation_center = match tetromino {
+            Tetromino::Line => Pos2f::new(1.5, -0.5),
+            Tetromino::O => Pos2f::new(0.5, 0.5),
+            Tetromino::L | Tetromino::J | Tetromino::Z | Tetromino::S | Tetromino::T => Pos2f::new(1.0, 0.0),
+        };
+
+        (flat_side_down_spawn_cells(tetromino), rotation_center)
+    }
+
+    fn kicks(&self, tetromino: Tetromino, from: RotationState, to: RotationState) -> &'static [(i32, i32)] {
+        use RotationState::*;
+
+        match tetromino {
+            Tetromino::O => &[(0, 0)],
+            Tetromino::Line => match (from, to) {
+                (Spawn, Right) => &[(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+                (Right, Spawn) => &[(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+                (Right, Two) => &[(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+                (Two, Right) => &[(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+                (Two, Left) => &[(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+                (Left, Two) => &[(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+                (Left, Spawn) => &[(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+                (Spawn, Left) => &[(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+                _ => SuperRotationSystem::half_turn_kicks(from, to),
+            },
+            Tetromino::L | Tetromino::J | Tetromino::S | Tetromino::Z | Tetromino::T => match (from, to) {
+                (Spawn, Right) => &[(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+                (Right, Spawn) => &[(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+                (Right, Two) => &[(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+                (Two, Right) => &[(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+                (Two, Left) => &[(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+                (Left, Two) => &[(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+                (Left, Spawn) => &[(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+                (Spawn, Left) => &[(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+                _ => SuperRotationSystem::half_turn_kicks(from, to),
+            },
+        }
+    }
+}
+
+/// The Arika Rotation System of the TGM series. Tetrominos spawn flat side up and stay in contact 
+/// with the bottom of their bounding box when rotated. The S, Z and I pieces only have two 
+/// orientations. A blocked rotation is retried one cell to the right and then one cell to the left, 
+/// except for the I and O piece which never kick. Following the center column rule of TGM, the L, J 
+/// and T pieces do not kick either if the first blocked cell of their bounding box, read row by row from 
+/// the top left, lies in its center column. 
+struct ArikaRotationSystem;
+
+impl ArikaRotationSystem {
+    /// Returns the cells of the tetromino in the given rotation state relative to its origin. 
+    fn cells(tetromino: Tetromino, rotation: RotationState) -> [(i32, i32); 4] {
+        use RotationState::*;
+
+        match (tetromino, rotation) {
+            (Tetromino::O, _) => [(0, 0), (1, 0), (0, 1), (1, 1)],
+            (Tetromino::Line, Spawn | Two) => [(0, 0), (1, 0), (2, 0), (3, 0)],
+            (Tetromino::Line, Right | Left) => [(2, 1), (2, 0), (2, -1), (2, -2)],
+            (Tetromino::S, Spawn | Two) => [(1, 1), (2, 1), (0, 0), (1, 0)],
+            (Tetromino::S, Right | Left) => [(0, 2), (0, 1), (1, 1), (1, 0)],
+            (Tetromino::Z, Spawn | Two) => [(0, 1), (1, 1), (1, 0), (2, 0)],
+            (Tetromino::Z, Right | Left) => [(2, 2), (1, 1), (2, 1), (1, 0)],
+            (Tetromino::T, Spawn) => [(0, 1), (1, 1), (2, 1), (1, 0)],
+            (Tetromino::T, Right) => [(1, 2), (0, 1), (1, 1), (1, 0)],
+            (Tetromino::T, Two) => [(1, 1), (0, 0), (1, 0), (2, 0)],
+            (Tetromino::T, Left) => [(1, 2), (1, 1), (2, 1), (1, 0)],
+            (Tetromino::J, Spawn) => [(0, 1), (1, 1), (2, 1), (2, 0)],
+            (Tetromino::J, Right) => [(1, 2), (1, 1), (0, 0), (1, 0)],
+            (Tetromino::J, Two) => [(0, 1), (0, 0), (1, 0), (2, 0)],
+            (Tetromino::J, Left) => [(1, 2), (2, 2), (1, 1), (1, 0)],
+            (Tetromino::L, Spawn) => [(0, 1), (1, 1), (2, 1), (0, 0)],
+            (Tetromino::L, Right) => [(0, 2), (1, 2), (1, 1), (1, 0)],
+            (Tetromino::L, Two) => [(0, 0), (1, 0), (2, 0), (2, 1)],
+            (Tetromino::L, Left) => [(1, 2), (1, 1), (1, 0), (2, 0)],
+        }
+    }
+}
+
+impl RotationSystem for ArikaRotationSystem {
+    fn spawn_shape(&self, tetromino: Tetromino) -> ([Pos2; 4], Pos2f) {
+        let origin = Pos2f::new(0.0, 0.0);
+
+        (cells_at_origin(ArikaRotationSystem::cells(tetromino, RotationState::Spawn), origin), origin)
+    }
+
+    fn spin(&self, tetromino: &mut PhysicalTetromino, spin_direction: SpinDirection) {
+        tetromino.rotation = tetromino.rotation.spin(spin_direction);
+        tetromino.coords = cells_at_origin(ArikaRotationSystem::cells(tetromino.tetromino, tetromino.rotation), tetromino.rotation_center);
+    }
+
+    fn kicks(&self, tetromino: Tetromino, _from: RotationState, _to: RotationState) -> &'static [(i32, i32)] {
+        match tetromino {
+            Tetromino::O | Tetromino::Line => &[(0, 0)],
+            Tetromino::L | Tetromino::J | Tetromino::S | Tetromino::Z | Tetromino::T => &[(0, 0), (1, 0), (-1, 0)],
+        }
+    }
+
+    fn prevents_kicks(&self, spun: &PhysicalTetromino, field: &TetrisField) -> bool {
+        if !matches!(spun.tetromino, Tetromino::L | Tetromino::J | Tetromino::T) {
+            return false;
+        }
+
+        let origin = Pos2::from(spun.rotation_center);
+        let first_blocked = (0..3).rev()
+            .flat_map(|y| (0..3).map(move |x| origin + Pos2::new(x, y)))
+            .find(|pos| spun.coords.contains(pos) && field.is_blocked(*pos));
+
+        first_blocked.is_some_and(|pos| pos.x == origin.x + 1)
+    }
+}
+
+/// The Nintendo Rotation System of the NES and Game Boy games. Tetrominos rotate around a fixed cell 
+/// and never kick. The S, Z and I pieces only have two orientations, whose vertical one leans to the 
+/// right. 
+struct NintendoRotationSystem;
+
+impl NintendoRotationSystem {
+    /// Returns the cells of the tetromino in the given rotation state relative to its origin. 
+    fn cells(tetromino: Tetromino, rotation: RotationState) -> [(i32, i32); 4] {
+        use RotationState::*;
+
+        match (tetromino, rotation) {
+            (Tetromino::O, _) => [(0, 0), (1, 0), (0, 1), (1, 1)],
+            (Tetromino::Line, Spawn | Two) => [(0, 0), (1, 0), (2, 0), (3, 0)],
+            (Tetromino::Line, Right | Left) => [(2, -2), (2, -1), (2, 0), (2, 1)],
+            (Tetromino::S, Spawn | Two) => [(1, 1), (2, 1), (0, 0), (1, 0)],
+            (Tetromino::S, Right | Left) => [(1, 2), (1, 1), (2, 1), (2, 0)],
+            (Tetromino::Z, Spawn | Two) => [(0, 1), (1, 1), (1, 0), (2, 0)],
+            (Tetromino::Z, Right | Left) => [(2, 2), (1, 1), (2, 1), (1, 0)],
+            (Tetromino::T, Spawn) => [(0, 1), (1, 1), (2, 1), (1, 0)],
+            (Tetromino::T, Right) => [(1, 2), (1, 1), (1, 0), (0, 1)],
+            (Tetromino::T, Two) => [(2, 1), (1, 1), (0, 1), (1, 2)],
+            (Tetromino::T, Left) => [(1, 0), (1, 1), (1, 2), (2, 1)],
+            (Tetromino::J, Spawn) => [(0, 1), (1, 1), (2, 1), (2, 0)],
+            (Tetromino::J, Right) => [(1, 2), (1, 1), (1, 0), (0, 0)],
+            (Tetromino::J, Two) => [(2, 1), (1, 1), (0, 1), (0, 2)],
+            (Tetromino::J, Left) => [(1, 0), (1, 1), (1, 2), (2, 2)],
+            (Tetromino::L, Spawn) => [(0, 1), (1, 1), (2, 1), (0, 0)],
+            (Tetromino::L, Right) => [(1, 2), (1, 1), (1, 0), (0, 2)],
+            (Tetromino::L, Two) => [(2, 1), (1, 1), (0, 1), (2, 2)],
+            (Tetromino::L, Left) => [(1, 0), (1, 1), (1, 2), (2, 0)],
+        }
+    }
+}
+
+impl RotationSystem for NintendoRotationSystem {
+    fn spawn_shape(&self, tetromino: Tetromino) -> ([Pos2; 4], Pos2f) {
+        let origin = Pos2f::new(0.0, 0.0);
+
+        (cells_at_origin(NintendoRotationSystem::cells(tetromino, RotationState::Spawn), origin), origin)
+    }
+
+    fn spin(&self, tetromino: &mut PhysicalTetromino, spin_direction: SpinDirection) {
+        tetromino.rotation = tetromino.rotation.spin(spin_direction);
+        tetromino.coords = cells_at_origin(NintendoRotationSystem::cells(tetromino.tetromino, tetromino.rotation), tetromino.rotation_center);
+    }
+
+    fn kicks(&self, _tetromino: Tetromino, _from: RotationState, _to: RotationState) -> &'static [(i32, i32)] {
+        &[(0, 0)]
+    }
+}
+
+/// The rotation system this game originally shipped with. Tetrominos rotate around a center that is 
+/// rounded down onto the grid and a blocked rotation is retried one cell higher. 
+struct ClassicRotationSystem;
+
+impl RotationSystem for ClassicRotationSystem {
+    fn spawn_shape(&self, tetromino: Tetromino) -> ([Pos2; 4], Pos2f) {
+        let rotation_center = match tetromino {
+            Tetromino::Line => Pos2f::new(1.5, 0.0),
+            Tetromino::O => Pos2f::new(0.5, 0.5),
+            Tetromino::L | Tetromino::J | Tetromino::Z | Tetromino::S => Pos2f::new(1.0, 0.5),
+            Tetromino::T => Pos2f::new(1.0, 0.0),
+        };
+
+        (flat_side_down_spawn_cells(tetromino), rotation_center)
+    }
+
+    fn kicks(&self, _tetromino: Tetromino, _from: RotationState, _to: RotationState) -> &'static [(i32, i32)] {
+        &[(0, 0), (0, 1)]
+    }
+}
+
+/// Returns the spawn cells shared by the Super and the Classic Rotation System, in which every 
+/// tetromino lies flat side down. 
+fn flat_side_down_spawn_cells(tetromino: Tetromino) -> [Pos2; 4] {
+    match tetromino {
+        Tetromino::Line => [Pos2::new(0, 0), Pos2::new(1, 0), Pos2::new(2, 0), Pos2::new(3, 0)],
+        Tetromino::O => [Pos2::new(0, 0), Pos2::new(0, 1), Pos2::new(1, 0), Pos2::new(1, 1)],
+        Tetromino::J => [Pos2::new(0, 0), Pos2::new(0, 1), Pos2::new(1, 0), Pos2::new(2, 0)],
+        Tetromino::L => [Pos2::new(0, 0), Pos2::new(1, 0), Pos2::new(2, 0), Pos2::new(2, 1)],
+        Tetromino::S => [Pos2::new(0, 0), Pos2::new(1, 0), Pos2::new(1, 1), Pos2::new(2, 1)],
+        Tetromino::Z => [Pos2::new(0, 1), Pos2::new(1, 1), Pos2::new(1, 0), Pos2::new(2, 0)],
+        Tetromino::T => [Pos2::new(0, 0), Pos2::new(1, 0), Pos2::new(2, 0), Pos2::new(1, 1)],
+    }
+}
+
+/// Translates a list of cells relative to an origin into absolute cell positions. Used by the rotation 
+/// systems which store the origin of their bounding box as rotation center. 
+fn cells_at_origin(cells: [(i32, i32); 4], origin: Pos2f) -> [Pos2; 4] {
+    let origin = Pos2::from(origin);
+
+    cells.map(|(x, y)| origin + Pos2::new(x, y))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use randomizer::FixedSequence;
+
+    /// Creates a game with the given rules which deals the given tetrominos over and over. 
+    fn game_with_sequence(rules: TetrisRules, sequence: &[Tetromino]) -> Tetris<ChaCha8Rng> {
+        Tetris::with_randomizer(ChaCha8Rng::seed_from_u64(0), rules, Box::new(FixedSequence::new(sequence.to_vec())))
+    }
+
+    #[test]
+    fn srs_kicks_of_opposite_spins_mirror_each_other() {
+        use RotationState::*;
+
+        let transitions = [(Spawn, Right), (Right, Two), (Two, Left), (Left, Spawn)];
+        for tetromino in [Tetromino::T, Tetromino::Line] {
+            for (from, to) in transitions {
+                let forth = SuperRotationSystem.kicks(tetromino, from, to);
+                let back = SuperRotationSystem.kicks(tetromino, to, from);
+
+                assert_eq!(forth.len(), 5);
+                for (kick, reverse_kick) in forth.iter().zip(back) {
+                    assert_eq!(*kick, (-reverse_kick.0, -reverse_kick.1), "{tetromino:?} {from:?} -> {to:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn srs_line_kicks_off_the_right_wall() {
+        let mut tetris = game_with_sequence(TetrisRules::default(), &[Tetromino::Line]);
+        tetris.spin_clock_90();
+        while tetris.try_right().is_ok() {}
+        tetris.drain_events().for_each(drop);
+
+        tetris.spin_counter_90();
+
+        assert_eq!(tetris.active_piece.rotation, RotationState::Spawn);
+        assert!(tetris.active_piece.coords.iter().all(|pos| pos.x < tetris.field.width as i32));
+        let events: Vec<GameEvent> = tetris.drain_events().collect();
+        assert!(matches!(events[..], [GameEvent::Rotated { kick, .. }] if kick != (0, 0)));
+    }
+
+    /// Creates a game dealing T pieces with the given rotation system and moves the first one down into 
+    /// the middle of the field. 
+    fn t_piece_in_the_open(rotation_system: RotationSystemKind) -> Tetris<ChaCha8Rng> {
+        let mut tetris = game_with_sequence(TetrisRules {rotation_system, ..Default::default()}, &[Tetromino::T]);
+        tetris.active_piece = tetris.active_piece + Pos2::new(0, -10);
+
+        tetris
+    }
+
+    /// Returns the cell positions of the piece relative to the lowest, left-most corner of its 
+    /// bounding box. 
+    fn shape(piece: &PhysicalTetromino) -> Vec<(i32, i32)> {
+        let min_x = piece.coords.iter().map(|pos| pos.x).min().unwrap();
+        let min_y = piece.coords.iter().map(|pos| pos.y).min().unwrap();
+        let mut shape: Vec<(i32, i32)> = piece.coords.iter().map(|pos| (pos.x - min_x, pos.y - min_y)).collect();
+        shape.sort();
+
+        shape
+    }
+
+    #[test]
+    fn ars_t_spawns_flat_side_up() {
+        let tetris = t_piece_in_the_open(RotationSystemKind::Ars);
+
+        assert_eq!(shape(&tetris.active_piece), [(0, 1), (1, 0), (1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn ars_kicks_a_piece_blocked_outside_the_center_column() {
+        let mut tetris = t_piece_in_the_open(RotationSystemKind::Ars);
+        let origin = Pos2::from(tetris.active_piece.rotation_center);
+        *tetris.field.get_mut(origin.x, origin.y + 1).unwrap() = CellStatus::Garbage;
+
+        tetris.spin_clock_90();
+
+        assert_eq!(tetris.active_piece.rotation, RotationState::Right);
+        assert_eq!(tetris.last_rotation_kick, Some((1, 0)));
+    }
+
+    #[test]
+    fn ars_center_column_rule_prevents_the_kick() {
+        let mut tetris = t_piece_in_the_open(RotationSystemKind::Ars);
+        let origin = Pos2::from(tetris.active_piece.rotation_center);
+        *tetris.field.get_mut(origin.x + 1, origin.y + 2).unwrap() = CellStatus::Garbage;
+        //kicked one cell to the right, the piece would fit
+        let mut kicked = tetris.active_piece;
+        ArikaRotationSystem.spin(&mut kicked, SpinDirection::Clockwise);
+        assert!(tetris.field.check_fit(&(kicked + Pos2::new(1, 0))).is_ok());
+
+        tetris.spin_clock_90();
+
+        assert_eq!(tetris.active_piece.rotation, RotationState::Spawn);
+        assert_eq!(tetris.last_rotation_kick, None);
+    }
+
+    #[test]
+    fn nrs_t_spawns_flat_side_up_and_rotates_around_its_center_cell() {
+        let mut tetris = t_piece_in_the_open(RotationSystemKind::Nrs);
+        let center = tetris.active_piece.coords[1];
+        assert_eq!(shape(&tetris.active_piece), [(0, 1), (1, 0), (1, 1), (2, 1)]);
+
+        for _ in 0..4 {
+            tetris.spin_clock_90();
+            assert!(tetris.active_piece.coords.contains(&center));
+        }
+
+        assert_eq!(shape(&tetris.active_piece), [(0, 1), (1, 0), (1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn nrs_blocked_rotation_does_not_kick() {
+        let mut tetris = t_piece_in_the_open(RotationSystemKind::Nrs);
+        let before = tetris.active_piece.coords;
+        let mut spun = tetris.active_piece;
+        NintendoRotationSystem.spin(&mut spun, SpinDirection::Clockwise);
+        let blocked = spun.coords.into_iter().find(|pos| !before.contains(pos)).unwrap();
+        *tetris.field.get_mut(blocked.x, blocked.y).unwrap() = CellStatus::Garbage;
+
+        tetris.spin_clock_90();
+
+        assert_eq!(tetris.active_piece.coords, before);
+        assert_eq!(tetris.active_piece.rotation, RotationState::Spawn);
+    }
+
+    #[test]
+    fn classic_t_spawns_flat_side_down_and_kicks_upwards() {
+        let mut tetris = t_piece_in_the_open(RotationSystemKind::Classic);
+        assert_eq!(shape(&tetris.active_piece), [(0, 0), (1, 0), (1, 1), (2, 0)]);
+        let mut spun = tetris.active_piece;
+        ClassicRotationSystem.spin(&mut spun, SpinDirection::Clockwise);
+        let lowest = spun.coords.into_iter().min_by_key(|pos| pos.y).unwrap();
+        *tetris.field.get_mut(lowest.x, lowest.y).unwrap() = CellStatus::Garbage;
+
+        tetris.spin_clock_90();
+
+        assert_eq!(tetris.active_piece.rotation, RotationState::Right);
+        assert_eq!(tetris.last_rotation_kick, Some((0, 1)));
+    }
+
+    #[test]
+    fn srs_half_turn_takes_the_first_kick_that_fits() {
+        let mut tetris = t_piece_in_the_open(RotationSystemKind::Srs);
+        let center = tetris.active_piece.coords[1];
+        //the upside down T does not fit in place, nor one cell higher
+        *tetris.field.get_mut(center.x, center.y - 1).unwrap() = CellStatus::Garbage;
+        *tetris.field.get_mut(center.x - 1, center.y + 1).unwrap() = CellStatus::Garbage;
+        tetris.drain_events().for_each(drop);
+
+        tetris.spin_180();
+
+        assert_eq!(tetris.active_piece.rotation, RotationState::Two);
+        let events: Vec<GameEvent> = tetris.drain_events().collect();
+        assert!(matches!(events[..], [GameEvent::Rotated { direction: SpinDirection::Half, kick: (1, 1) }]), "{events:?}");
+        assert_eq!(tetris.active_piece.coords[1], center + Pos2::new(1, 1));
+    }
+
+    #[test]
+    fn t_spin_double_is_detected_and_clears_both_rows() {
+        let mut tetris = game_with_sequence(TetrisRules::default(), &[Tetromino::T]);
+        //a T slot in the middle of the two bottom rows with an overhang on its left
+        for x in 0..tetris.field.width as i32 {
+            if x != 4 {
+                *tetris.field.get_mut(x, 0).unwrap() = CellStatus::Garbage;
+            }
+            if !(3..=5).contains(&x) {
+                *tetris.field.get_mut(x, 1).unwrap() = CellStatus::Garbage;
+            }
+        }
+        *tetris.field.get_mut(3, 2).unwrap() = CellStatus::Garbage;
+
+        tetris.spin_clock_90();
+        while tetris.drop() == Ok(DropOutcome::Moved) {}
+        tetris.spin_clock_90();
+        let outcome = tetris.drop_completely_down().unwrap();
+
+        assert_eq!(outcome.cells_dropped, 0);
+        assert_eq!(outcome.lock_result.t_spin, TSpin::Full);
+        assert_eq!(outcome.lock_result.rows, vec![0, 1]);
+    }
+
+    #[test]
+    fn grounded_piece_locks_once_its_move_resets_are_used_up() {
+        let mut tetris = game_with_sequence(TetrisRules::default(), &[Tetromino::O]);
+        while tetris.drop() == Ok(DropOutcome::Moved) {}
+
+        //every move restarts the lock delay before it runs out, until the resets are used up
+        for i in 0..LOCK_RESET_MOVE_LIMIT {
+            assert_eq!(tetris.tick(Duration::from_millis(400)), Ok(None));
+            if i % 2 == 0 {
+                tetris.try_left().unwrap();
+            } else {
+                tetris.try_right().unwrap();
+            }
+        }
+
+        assert!(matches!(tetris.tick(Duration::from_millis(1)), Ok(Some(_))));
+    }
+
+    #[test]
+    fn garbage_is_inserted_below_the_stack_with_a_hole() {
+        let mut tetris = game_with_sequence(TetrisRules::default(), &[Tetromino::O]);
+        tetris.add_garbage(2, 7);
+
+        tetris.drop_completely_down().unwrap();
+
+        for y in 0..2 {
+            for x in 0..tetris.field.width as i32 {
+                let expected = if x == 7 {CellStatus::Empty} else {CellStatus::Garbage};
+                assert_eq!(tetris.field.get(x, y), Some(expected), "({x}, {y})");
+            }
+        }
+        //the locked O has been pushed up on top of the garbage
+        assert_eq!(tetris.field.get(4, 2), Some(CellStatus::Yellow));
+    }
+
+    #[test]
+    fn locking_with_pending_garbage_keeps_the_locked_piece_on_the_stack() {
+        let mut tetris = game_with_sequence(TetrisRules::default(), &[Tetromino::O]);
+        let spawn_coords = tetris.active_piece.coords;
+        tetris.add_garbage(2, 0);
+        tetris.drain_events().for_each(drop);
+
+        tetris.drop_completely_down().unwrap();
+
+        let events: Vec<GameEvent> = tetris.drain_events().collect();
+        assert!(matches!(
+            events[..], 
+            [GameEvent::HardDropped(_), GameEvent::Locked(_), GameEvent::GarbageInserted(2), GameEvent::PieceSpawned(_)],
+        ), "{events:?}");
+        let locked: Vec<(u32, u32)> = tetris.get_block_list()
+            .into_iter()
+            .filter(|(cell, ..)| *cell == CellStatus::Yellow)
+            .map(|(_, x, y)| (x, y))
+            .collect();
+        assert_eq!(locked.len(), 4);
+        assert!(locked.iter().all(|(_, y)| (2..4).contains(y)), "{locked:?}");
+        assert_eq!(tetris.active_piece.coords, spawn_coords);
+    }
+
+    /// Returns the active piece of a new game with the given seed and rules followed by the next 
+    /// pieces it deals. 
+    fn dealt_pieces(seed: u64, rules: TetrisRules) -> Vec<Tetromino> {
+        let mut tetris = Tetris::new(ChaCha8Rng::seed_from_u64(seed), rules);
+
+        std::iter::once(tetris.active_piece.tetromino)
+            .chain((&mut tetris.iterator).take(49))
+            .collect()
+    }
+
+    #[test]
+    fn the_same_seed_deals_the_same_pieces() {
+        for randomizer in randomizer::RandomizerKind::all_randomizer_array() {
+            let rules = TetrisRules {randomizer, ..Default::default()};
+
+            assert_eq!(dealt_pieces(7, rules), dealt_pieces(7, rules), "{randomizer:?}");
+        }
+    }
+
+    #[test]
+    fn different_seeds_deal_different_pieces() {
+        for randomizer in randomizer::RandomizerKind::all_randomizer_array() {
+            if randomizer == randomizer::RandomizerKind::Fixed {
+                continue;
+            }
+            let rules = TetrisRules {randomizer, ..Default::default()};
+
+            assert_ne!(dealt_pieces(7, rules), dealt_pieces(8, rules), "{randomizer:?}");
+        }
+    }
+
+    #[test]
+    fn cheese_rows_do_not_change_the_dealt_pieces() {
+        let rules = TetrisRules {randomizer: randomizer::RandomizerKind::SevenBag, ..Default::default()};
+        let cheese_rules = TetrisRules {cheese_rows: 6, ..rules};
+        let plain = Tetris::new(ChaCha8Rng::seed_from_u64(3), rules);
+        let mut cheese = Tetris::new(ChaCha8Rng::seed_from_u64(3), cheese_rules);
+        let mut cheese_rng = ChaCha8Rng::seed_from_u64(3);
+        cheese_rng.set_stream(2);
+
+        cheese.add_cheese_rows(&mut cheese_rng);
+
+        for y in 0..6 {
+            let holes = (0..cheese.field.width as i32).filter(|x| cheese.field.get(*x, y) == Some(CellStatus::Empty)).count();
+            assert_eq!(holes, 1, "row {y}");
+        }
+        assert_eq!(cheese.active_piece.tetromino, plain.active_piece.tetromino);
+        assert!(cheese.iterator.peek().eq(plain.iterator.peek()));
+    }
+
+    #[test]
+    fn garbage_pushing_the_stack_past_the_top_ends_the_game() {
+        let mut tetris = game_with_sequence(TetrisRules::default(), &[Tetromino::O]);
+        let height = tetris.field.height as i32;
+        for y in 0..height - 4 {
+            *tetris.field.get_mut(0, y).unwrap() = CellStatus::Garbage;
+        }
+        tetris.add_garbage(5, 9);
+
+        assert_eq!(tetris.drop_completely_down(), Err(GameOver::TopOut));
+        assert!(tetris.drain_events().any(|event| event == GameEvent::GameOver(GameOver::TopOut)));
+    }
+
+    #[test]
+    fn first_hold_pulls_in_the_next_piece() {
+        let mut tetris = game_with_sequence(TetrisRules::default(), &[Tetromino::T, Tetromino::S, Tetromino::Z]);
+        tetris.drain_events().for_each(drop);
+
+        tetris.try_switch_active_piece().unwrap();
+
+        let events: Vec<GameEvent> = tetris.drain_events().collect();
+        assert_eq!(events, [GameEvent::Held(Tetromino::T), GameEvent::PieceSpawned(Tetromino::S)]);
+
+        assert_eq!(tetris.active_piece.tetromino, Tetromino::S);
+        assert_eq!(tetris.stored_piece.map(|piece| piece.tetromino), Some(Tetromino::T));
+        assert_eq!(tetris.iterator.peek().next(), Some(&Tetromino::Z));
+    }
+
+    #[test]
+    fn hold_once_rejects_a_second_hold_until_the_piece_locks() {
+        let mut tetris = game_with_sequence(TetrisRules::default(), &[Tetromino::T, Tetromino::S]);
+
+        tetris.try_switch_active_piece().unwrap();
+        assert_eq!(tetris.try_switch_active_piece(), Err(MoveError::HoldAlreadyUsed));
+
+        tetris.drop_completely_down().unwrap();
+        assert_eq!(tetris.try_switch_active_piece(), Ok(()));
+    }
+
+    #[test]
+    fn hold_unlimited_switches_back_and_forth() {
+        let rules = TetrisRules {hold: HoldRule::Unlimited, ..Default::default()};
+        let mut tetris = game_with_sequence(rules, &[Tetromino::T, Tetromino::S]);
+
+        tetris.try_switch_active_piece().unwrap();
+        tetris.drain_events().for_each(drop);
+        tetris.try_switch_active_piece().unwrap();
+
+        //the stored piece comes back without a new piece being dealt
+        assert!(tetris.drain_events().eq([GameEvent::Held(Tetromino::S)]));
+        assert_eq!(tetris.active_piece.tetromino, Tetromino::T);
+        assert_eq!(tetris.stored_piece.map(|piece| piece.tetromino), Some(Tetromino::S));
+    }
+
+    #[test]
+    fn hold_disabled_rejects_every_hold() {
+        let rules = TetrisRules {hold: HoldRule::Disabled, ..Default::default()};
+        let mut tetris = game_with_sequence(rules, &[Tetromino::T, Tetromino::S]);
+
+        assert_eq!(tetris.try_switch_active_piece(), Err(MoveError::HoldDisabled));
+        assert_eq!(tetris.active_piece.tetromino, Tetromino::T);
+        assert!(tetris.stored_piece.is_none());
+    }
 }
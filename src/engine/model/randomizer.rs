@@ -0,0 +1,406 @@
+use rand::{Rng, RngCore};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+use super::Tetromino;
+
+const TGM_HISTORY_LENGTH: usize = 4;
+const TGM_ROLLS: u32 = 6;
+
+/// A randomizer decides which tetromino is dealt next. All randomness has to be drawn from the given 
+/// random number generator, so that a game with the same generator deals the same pieces. 
+pub trait Randomizer: std::fmt::Debug + Send + Sync {
+    /// Returns the next tetromino to be dealt. 
+    fn next_tetromino(&mut self, rng: &mut dyn RngCore) -> Tetromino;
+
+    /// Returns a boxed copy of this randomizer in its current state. 
+    fn clone_box(&self) -> Box<dyn Randomizer>;
+
+    /// Returns a copy of this randomizer in its current state which can be saved. 
+    fn state(&self) -> RandomizerState;
+}
+
+impl Clone for Box<dyn Randomizer> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+impl Serialize for Box<dyn Randomizer> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.state().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Box<dyn Randomizer> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(RandomizerState::deserialize(deserializer)?.randomizer())
+    }
+}
+
+/// The saved state of any of the randomizers of this module. 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum RandomizerState {
+    SevenBag(SevenBag),
+    FourteenBag(FourteenBag),
+    Random(PureRandom),
+    Nes(NesRandomizer),
+    Tgm(TgmRandomizer),
+    Fixed(FixedSequence),
+}
+
+impl RandomizerState {
+    /// Creates a randomizer continuing from this state. 
+    pub fn randomizer(self) -> Box<dyn Randomizer> {
+        match self {
+            Self::SevenBag(randomizer) => Box::new(randomizer),
+            Self::FourteenBag(randomizer) => Box::new(randomizer),
+            Self::Random(randomizer) => Box::new(randomizer),
+            Self::Nes(randomizer) => Box::new(randomizer),
+            Self::Tgm(randomizer) => Box::new(randomizer),
+            Self::Fixed(randomizer) => Box::new(randomizer),
+        }
+    }
+}
+
+/// Returns a random index below the given length. The index is drawn as a u32, as drawing a usize 
+/// would deal differently on 32 and 64 bit platforms. 
+fn random_index(rng: &mut dyn RngCore, len: usize) -> usize {
+    rng.gen_range(0..len as u32) as usize
+}
+
+/// The randomizers which can be chosen for a [super::Tetris] game in its [super::TetrisRules]. 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RandomizerKind {
+    /// See [SevenBag]. 
+    #[default]
+    SevenBag,
+    /// See [FourteenBag]. 
+    FourteenBag,
+    /// See [PureRandom]. 
+    Random,
+    /// See [NesRandomizer]. 
+    Nes,
+    /// See [TgmRandomizer]. 
+    Tgm,
+    /// See [FixedSequence]. The sequence has to be supplied with [super::Tetris::with_randomizer], on its 
+    /// own this deals all 7 tetrominos in a fixed order. 
+    Fixed,
+}
+
+impl RandomizerKind {
+    /// Returns an array of all randomizers. 
+    pub fn all_randomizer_array() -> [Self; 6] {
+        [
+            Self::SevenBag,
+            Self::FourteenBag,
+            Self::Random,
+            Self::Nes,
+            Self::Tgm,
+            Self::Fixed,
+        ]
+    }
+
+    /// Creates a fresh randomizer of this kind. 
+    pub fn randomizer(self) -> Box<dyn Randomizer> {
+        match self {
+            Self::SevenBag => Box::new(SevenBag::default()),
+            Self::FourteenBag => Box::new(FourteenBag::default()),
+            Self::Random => Box::new(PureRandom),
+            Self::Nes => Box::new(NesRandomizer::default()),
+            Self::Tgm => Box::new(TgmRandomizer::default()),
+            Self::Fixed => Box::new(FixedSequence::new(Vec::new())),
+        }
+    }
+}
+
+/// The randomizer of the Tetris guideline: <br/> 
+/// Take a list of 7 distinct Tetrominos, shuffle them and then return the list in order. When 
+/// all 7 have been dealt out, get a new list of tetrominos and repeat. This guarantees a repeat of 
+/// the same tetromino after at most 12 others. 
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SevenBag {
+    bag: Vec<Tetromino>,
+}
+
+impl Randomizer for SevenBag {
+    fn next_tetromino(&mut self, rng: &mut dyn RngCore) -> Tetromino {
+        if self.bag.is_empty() {
+            self.bag.extend_from_slice(&Tetromino::all_tetromino_array());
+            self.bag.shuffle(rng);
+        }
+
+        self.bag.pop().unwrap()
+    }
+
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+
+    fn state(&self) -> RandomizerState {
+        RandomizerState::SevenBag(self.clone())
+    }
+}
+
+/// Like the [SevenBag], but the bag holds two of every tetromino, which allows for more repeats. 
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct FourteenBag {
+    bag: Vec<Tetromino>,
+}
+
+impl Randomizer for FourteenBag {
+    fn next_tetromino(&mut self, rng: &mut dyn RngCore) -> Tetromino {
+        if self.bag.is_empty() {
+            self.bag.extend_from_slice(&Tetromino::all_tetromino_array());
+            self.bag.extend_from_slice(&Tetromino::all_tetromino_array());
+            self.bag.shuffle(rng);
+        }
+
+        self.bag.pop().unwrap()
+    }
+
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+
+    fn state(&self) -> RandomizerState {
+        RandomizerState::FourteenBag(self.clone())
+    }
+}
+
+/// Deals every tetromino with the same probability, regardless of what has been dealt before. 
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct PureRandom;
+
+impl Randomizer for PureRandom {
+    fn next_tetromino(&mut self, rng: &mut dyn RngCore) -> Tetromino {
+        *Tetromino::all_tetromino_array().choose(rng).unwrap()
+    }
+
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(*self)
+    }
+
+    fn state(&self) -> RandomizerState {
+        RandomizerState::Random(*self)
+    }
+}
+
+/// The randomizer of the NES game. It rolls an eighth "reroll" option alongside the 7 tetrominos. If 
+/// that or the previous tetromino is rolled, it rolls once more among the 7 tetrominos and takes that 
+/// result, whatever it is. 
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct NesRandomizer {
+    previous: Option<Tetromino>,
+}
+
+impl Randomizer for NesRandomizer {
+    fn next_tetromino(&mut self, rng: &mut dyn RngCore) -> Tetromino {
+        let all = Tetromino::all_tetromino_array();
+
+        let roll = random_index(rng, all.len() + 1);
+        let tetromino = match all.get(roll) {
+            Some(tetromino) if Some(*tetromino) != self.previous => *tetromino,
+            _ => all[random_index(rng, all.len())],
+        };
+
+        self.previous = Some(tetromino);
+        tetromino
+    }
+
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(*self)
+    }
+
+    fn state(&self) -> RandomizerState {
+        RandomizerState::Nes(*self)
+    }
+}
+
+/// The randomizer of the TGM series. It remembers the last 4 dealt tetrominos and rolls up to 6 times 
+/// for one that is not among them, taking the last roll if all of them were. The history starts out as 
+/// Z, S, S, Z and the first tetromino is never an S, Z or O. 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TgmRandomizer {
+    history: [Tetromino; TGM_HISTORY_LENGTH],
+    first: bool,
+}
+
+impl Default for TgmRandomizer {
+    fn default() -> Self {
+        Self {
+            history: [Tetromino::Z, Tetromino::S, Tetromino::S, Tetromino::Z],
+            first: true,
+        }
+    }
+}
+
+impl Randomizer for TgmRandomizer {
+    fn next_tetromino(&mut self, rng: &mut dyn RngCore) -> Tetromino {
+        let tetromino = if self.first {
+            self.first = false;
+            *[Tetromino::Line, Tetromino::J, Tetromino::L, Tetromino::T].choose(rng).unwrap()
+        } else {
+            let all = Tetromino::all_tetromino_array();
+            let mut tetromino = all[0];
+            for _ in 0..TGM_ROLLS {
+                tetromino = all[random_index(rng, all.len())];
+                if !self.history.contains(&tetromino) {
+                    break;
+                }
+            }
+            tetromino
+        };
+
+        self.history.rotate_left(1);
+        self.history[TGM_HISTORY_LENGTH - 1] = tetromino;
+        tetromino
+    }
+
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+
+    fn state(&self) -> RandomizerState {
+        RandomizerState::Tgm(self.clone())
+    }
+}
+
+/// Deals the tetrominos of a fixed list in order and starts over once the list has been dealt out. 
+/// An empty list deals all 7 tetrominos in a fixed order instead. 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "FixedSequenceSerialized")]
+pub struct FixedSequence {
+    sequence: Vec<Tetromino>,
+    index: usize,
+}
+
+/// A helper struct for deserializing a [FixedSequence]. A saved sequence may be empty or have an index 
+/// past its end, which would make dealing from it panic. 
+#[derive(Deserialize)]
+struct FixedSequenceSerialized {
+    sequence: Vec<Tetromino>,
+    index: usize,
+}
+
+impl From<FixedSequenceSerialized> for FixedSequence {
+    fn from(value: FixedSequenceSerialized) -> Self {
+        let mut fixed_sequence = FixedSequence::new(value.sequence);
+        fixed_sequence.index = value.index % fixed_sequence.sequence.len();
+        fixed_sequence
+    }
+}
+
+impl FixedSequence {
+    /// Creates a new instance dealing the given list. 
+    pub fn new(mut sequence: Vec<Tetromino>) -> Self {
+        if sequence.is_empty() {
+            sequence.extend_from_slice(&Tetromino::all_tetromino_array());
+        }
+
+        Self {
+            sequence,
+            index: 0,
+        }
+    }
+}
+
+impl Randomizer for FixedSequence {
+    fn next_tetromino(&mut self, _rng: &mut dyn RngCore) -> Tetromino {
+        let tetromino = self.sequence[self.index];
+        self.index = (self.index + 1) % self.sequence.len();
+        tetromino
+    }
+
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+
+    fn state(&self) -> RandomizerState {
+        RandomizerState::Fixed(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    /// Serializes the randomizer and deserializes it again, like saving and resuming a game does. 
+    fn save_and_load(randomizer: &dyn Randomizer) -> Box<dyn Randomizer> {
+        let bytes = serde_cbor::to_vec(&randomizer.state()).unwrap();
+        serde_cbor::from_slice::<RandomizerState>(&bytes).unwrap().randomizer()
+    }
+
+    #[test]
+    fn loaded_fixed_sequence_with_invalid_state_still_deals() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+        let mut past_the_end = save_and_load(&FixedSequence {sequence: vec![Tetromino::T, Tetromino::O], index: 5});
+        assert_eq!(past_the_end.next_tetromino(&mut rng), Tetromino::O);
+
+        let mut empty = save_and_load(&FixedSequence {sequence: Vec::new(), index: 0});
+        let dealt: Vec<Tetromino> = (0..7).map(|_| empty.next_tetromino(&mut rng)).collect();
+        assert_eq!(dealt, Tetromino::all_tetromino_array());
+    }
+
+    #[test]
+    fn seven_bag_deals_every_tetromino_once_per_bag() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut randomizer = SevenBag::default();
+
+        for _ in 0..10 {
+            let bag: Vec<Tetromino> = (0..7).map(|_| randomizer.next_tetromino(&mut rng)).collect();
+            for tetromino in Tetromino::all_tetromino_array() {
+                assert_eq!(bag.iter().filter(|dealt| **dealt == tetromino).count(), 1, "{bag:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn fourteen_bag_deals_every_tetromino_twice_per_bag() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut randomizer = FourteenBag::default();
+
+        for _ in 0..10 {
+            let bag: Vec<Tetromino> = (0..14).map(|_| randomizer.next_tetromino(&mut rng)).collect();
+            for tetromino in Tetromino::all_tetromino_array() {
+                assert_eq!(bag.iter().filter(|dealt| **dealt == tetromino).count(), 2, "{bag:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn tgm_never_starts_with_s_z_or_o() {
+        for seed in 0..100 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let first = TgmRandomizer::default().next_tetromino(&mut rng);
+            assert!(![Tetromino::S, Tetromino::Z, Tetromino::O].contains(&first), "{first:?}");
+        }
+    }
+
+    #[test]
+    fn same_seed_deals_the_same_pieces() {
+        for kind in RandomizerKind::all_randomizer_array() {
+            let mut first = kind.randomizer();
+            let mut second = kind.randomizer();
+            let mut first_rng = ChaCha8Rng::seed_from_u64(7);
+            let mut second_rng = ChaCha8Rng::seed_from_u64(7);
+
+            for _ in 0..50 {
+                assert_eq!(first.next_tetromino(&mut first_rng), second.next_tetromino(&mut second_rng), "{kind:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn fixed_sequence_starts_over_after_the_last_tetromino() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut randomizer = FixedSequence::new(vec![Tetromino::L, Tetromino::J, Tetromino::Line]);
+
+        let dealt: Vec<Tetromino> = (0..5).map(|_| randomizer.next_tetromino(&mut rng)).collect();
+        assert_eq!(dealt, [Tetromino::L, Tetromino::J, Tetromino::Line, Tetromino::L, Tetromino::J]);
+    }
+}
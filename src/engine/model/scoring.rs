@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+
+const LINES_PER_LEVEL: u32 = 10;
+
+/// The scoring tables a [super::Tetris] game can be scored with. 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScoringRules {
+    /// The scoring of the Tetris guideline. Line clears and T-spins are multiplied by the level, 
+    /// consecutive difficult clears earn a back-to-back bonus, consecutive clears build up a combo and 
+    /// clearing the entire field earns a perfect clear bonus. 
+    #[default]
+    Guideline,
+    /// The scoring of the NES game. Only line clears, multiplied by the level, and dropped cells count. 
+    Nintendo,
+}
+
+impl ScoringRules {
+    /// Returns an array of all scoring rules. 
+    pub fn all_scoring_rules_array() -> [Self; 2] {
+        [
+            Self::Guideline,
+            Self::Nintendo,
+        ]
+    }
+
+    /// Returns the points for every cell the active piece is dropped by the player. 
+    fn drop_points_per_cell(self, hard_drop: bool) -> u32 {
+        match (self, hard_drop) {
+            (Self::Guideline, false) => 1,
+            (Self::Guideline, true) => 2,
+            (Self::Nintendo, _) => 1,
+        }
+    }
+}
+
+/// The number of garbage rows a lock sends to the opponent in a versus game. 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttackTable {
+    /// The garbage sent for clearing 1 to 4 lines. 
+    pub lines: [u32; 4],
+    /// The garbage sent for a full T-spin clearing 1 to 3 lines. 
+    pub t_spin_lines: [u32; 3],
+    /// The garbage sent for a perfect clear, replacing the garbage for the cleared lines. 
+    pub perfect_clear: u32,
+}
+
+impl Default for AttackTable {
+    fn default() -> Self {
+        Self {
+            lines: [0, 1, 2, 4],
+            t_spin_lines: [2, 4, 6],
+            perfect_clear: 10,
+        }
+    }
+}
+
+impl AttackTable {
+    /// Returns the garbage sent for a lock which cleared the given number of lines. 
+    pub(super) fn attack(&self, lines: u32, t_spin: TSpin, perfect_clear: bool) -> u32 {
+        if lines == 0 {
+            return 0;
+        }
+        if perfect_clear {
+            return self.perfect_clear;
+        }
+
+        let index = lines.min(4) as usize - 1;
+        match t_spin {
+            TSpin::Full => self.t_spin_lines[index.min(2)],
+            TSpin::None | TSpin::Mini => self.lines[index],
+        }
+    }
+}
+
+/// Whether and how the active piece was spun into place right before it locked. 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TSpin {
+    #[default]
+    None,
+    /// A T-spin where only one of the two corners in front of the T is occupied. 
+    Mini,
+    /// A T-spin where both corners in front of the T are occupied. 
+    Full,
+}
+
+/// The score of a running game along with everything the scoring rules need to remember between 
+/// two locks. 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Score {
+    pub points: u32,
+    /// The level, starting at 0. Line clears are multiplied by the level plus one. 
+    pub level: u32,
+    pub lines: u32,
+    /// The number of consecutive locks that cleared lines, minus one. None if the last lock cleared 
+    /// nothing. 
+    pub combo: Option<u32>,
+    /// Whether the last line clear was a difficult one, i.e. a Tetris or a T-spin clearing lines. 
+    pub back_to_back: bool,
+}
+
+impl Score {
+    /// Adds the points for the given number of cells the player dropped the active piece by. Returns the 
+    /// added points. 
+    pub(super) fn add_drop(&mut self, rules: ScoringRules, cells: u32, hard_drop: bool) -> u32 {
+        let points = cells * rules.drop_points_per_cell(hard_drop);
+        self.points += points;
+        points
+    }
+
+    /// Adds the points for a locked piece which cleared the given number of lines and updates the level, 
+    /// combo and back-to-back state. Returns the added points. 
+    pub(super) fn add_lock(&mut self, rules: ScoringRules, lines: u32, t_spin: TSpin, perfect_clear: bool) -> u32 {
+        let multiplier = self.level + 1;
+        let points = match rules {
+            ScoringRules::Guideline => {
+                let mut base = match (t_spin, lines) {
+                    (TSpin::None, 0) => 0,
+                    (TSpin::None, 1) => 100,
+                    (TSpin::None, 2) => 300,
+                    (TSpin::None, 3) => 500,
+                    (TSpin::None, _) => 800,
+                    (TSpin::Mini, 0) => 100,
+                    (TSpin::Mini, 1) => 200,
+                    (TSpin::Mini, _) => 400,
+                    (TSpin::Full, 0) => 400,
+                    (TSpin::Full, 1) => 800,
+                    (TSpin::Full, 2) => 1200,
+                    (TSpin::Full, _) => 1600,
+                };
+
+                //back-to-back bonus for consecutive difficult clears
+                let difficult = lines >= 4 || (t_spin != TSpin::None && lines > 0);
+                let back_to_back = difficult && self.back_to_back;
+                if back_to_back {
+                    base = base * 3 / 2;
+                }
+                //a T-spin without lines neither continues nor breaks the chain
+                if lines > 0 {
+                    self.back_to_back = difficult;
+                }
+
+                //combo bonus for consecutive clears
+                self.combo = if lines > 0 {Some(self.combo.map_or(0, |combo| combo + 1))} else {None};
+                let combo_bonus = 50 * self.combo.unwrap_or(0);
+
+                let perfect_clear_bonus = match (perfect_clear, lines) {
+                    (false, _) | (true, 0) => 0,
+                    (true, 1) => 800,
+                    (true, 2) => 1200,
+                    (true, 3) => 1800,
+                    (true, _) => if back_to_back {3200} else {2000},
+                };
+
+                (base + combo_bonus + perfect_clear_bonus) * multiplier
+            }
+            ScoringRules::Nintendo => {
+                let base = match lines {
+                    0 => 0,
+                    1 => 40,
+                    2 => 100,
+                    3 => 300,
+                    _ => 1200,
+                };
+
+                base * multiplier
+            }
+        };
+
+        self.points += points;
+        self.lines += lines;
+        self.level = self.lines / LINES_PER_LEVEL;
+        points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn back_to_back_tetris_earns_the_bonus() {
+        let mut score = Score::default();
+
+        assert_eq!(score.add_lock(ScoringRules::Guideline, 4, TSpin::None, false), 800);
+        assert!(score.back_to_back);
+        //1.5 times the Tetris plus the bonus of a combo of one
+        assert_eq!(score.add_lock(ScoringRules::Guideline, 4, TSpin::None, false), 1200 + 50);
+        assert!(score.back_to_back);
+
+        score.add_lock(ScoringRules::Guideline, 1, TSpin::None, false);
+        assert!(!score.back_to_back);
+    }
+
+    #[test]
+    fn t_spin_without_lines_keeps_the_back_to_back_chain() {
+        let mut score = Score::default();
+        score.add_lock(ScoringRules::Guideline, 4, TSpin::None, false);
+
+        assert_eq!(score.add_lock(ScoringRules::Guideline, 0, TSpin::Full, false), 400);
+        assert!(score.back_to_back);
+        assert_eq!(score.combo, None);
+        assert_eq!(score.add_lock(ScoringRules::Guideline, 2, TSpin::Full, false), 1800);
+    }
+}
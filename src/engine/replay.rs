@@ -0,0 +1,420 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::model::{Tetris, TetrisRules, Tetromino};
+use crate::engine::scene::{Game, GameRng};
+use crate::ui::TetrisInstruction;
+
+const REPLAY_DIRECTORY: &str = "data/replays";
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+const PLAYBACK_MIN_SPEED: f32 = 0.25;
+const PLAYBACK_MAX_SPEED: f32 = 8.0;
+
+/// Everything needed to play a game again: the seed and rules it was dealt with and every action in 
+/// the order it happened. The time an action happened at is the sum of all ticks before it. 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    pub rules: TetrisRules,
+    /// The pieces dealt by the fixed sequence randomizer, empty for every other randomizer. 
+    pub sequence: Vec<Tetromino>,
+    pub actions: Vec<ReplayAction>,
+    /// When the game was started, in seconds since the unix epoch. Together with the seed it names the 
+    /// file the replay is saved to. 
+    #[serde(default)]
+    pub started: u64,
+    /// Whether the last action is a tick the next tick can be merged into, see [Replay::record]. 
+    #[serde(skip)]
+    tick_open: bool,
+}
+
+/// A single recorded action of a [Replay]. 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReplayAction {
+    /// A frame has passed, which runs down the lock delay. 
+    Tick(Duration),
+    /// The active piece dropped on its own. 
+    Gravity,
+    /// The player gave an instruction. 
+    Instruction(TetrisInstruction),
+}
+
+impl ReplayAction {
+    /// Performs the action on the given game. 
+    pub fn apply(self, tetris: &mut Tetris<GameRng>) {
+        match self {
+            Self::Tick(delta) => {
+                let _ = tetris.tick(delta);
+            }
+            Self::Gravity => {
+                let _ = tetris.drop();
+            }
+            Self::Instruction(instruction) => match instruction {
+                TetrisInstruction::Left => {
+                    let _ = tetris.try_left();
+                }
+                TetrisInstruction::Right => {
+                    let _ = tetris.try_right();
+                }
+                TetrisInstruction::Drop => {
+                    let _ = tetris.soft_drop();
+                }
+                TetrisInstruction::FullDrop => {
+                    let _ = tetris.drop_completely_down();
+                }
+                TetrisInstruction::RotateCounter => tetris.spin_counter_90(),
+                TetrisInstruction::RotateClock => tetris.spin_clock_90(),
+                TetrisInstruction::Rotate180 => tetris.spin_180(),
+                TetrisInstruction::Store => {
+                    let _ = tetris.try_switch_active_piece();
+                }
+                TetrisInstruction::Forfeit => tetris.forfeit(),
+                //meta instructions are filtered out of the input queues before they are applied
+                TetrisInstruction::Pause | TetrisInstruction::QuickRestart | TetrisInstruction::ToggleGhost => {}
+            },
+        }
+    }
+}
+
+impl Replay {
+    /// Creates an empty replay for a game dealt with the given seed, rules and fixed sequence, which is 
+    /// started right now. 
+    pub fn new(seed: u64, rules: TetrisRules, sequence: Vec<Tetromino>) -> Self {
+        let started = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |since_epoch| since_epoch.as_secs());
+
+        Self {
+            seed,
+            rules,
+            sequence,
+            actions: Vec::new(),
+            started,
+            tick_open: false,
+        }
+    }
+
+    /// Appends an action performed on the game. Consecutive ticks are merged into a single one, so the 
+    /// replay grows with the actions of the player instead of the frame rate. A tick which locked the 
+    /// piece is never merged with the next one, as the rest of the merged tick would then no longer run 
+    /// down the lock delay of the next piece. 
+    pub fn record(&mut self, action: ReplayAction, locked: bool) {
+        match (action, self.actions.last_mut()) {
+            (ReplayAction::Tick(delta), Some(ReplayAction::Tick(last))) if self.tick_open => *last += delta,
+            _ => self.actions.push(action),
+        }
+        self.tick_open = matches!(action, ReplayAction::Tick(_)) && !locked;
+    }
+
+    /// Creates the game in the state it was in before the first action. 
+    pub fn game(&self) -> Game {
+        Game::new(self.seed, self.rules, self.sequence.clone())
+    }
+
+    /// Returns the time from the start of the game until its last action. 
+    pub fn length(&self) -> Duration {
+        self.actions.iter()
+            .map(|action| match action {
+                ReplayAction::Tick(delta) => *delta,
+                _ => Duration::ZERO,
+            })
+            .sum()
+    }
+
+    /// Returns the file in the data/replays directory the replay is saved to, named after the time the 
+    /// game was started and its seed. 
+    pub fn file_path(&self) -> PathBuf {
+        Path::new(REPLAY_DIRECTORY).join(format!("{}_{}.dat", format_timestamp(self.started), self.seed))
+    }
+
+    /// Tries to save the replay to its file in the data/replays directory, replacing the replay saved 
+    /// earlier for the same game. Returns Err if any problems appear. 
+    pub fn write_to_file(&self) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::create_dir_all(REPLAY_DIRECTORY)?;
+
+        //get a file
+        let file = std::fs::File::create(self.file_path())?;
+
+        //serialize and write to file
+        serde_cbor::to_writer(file, &self)?;
+
+        Ok(())
+    }
+
+    /// Reads the replay from the given file. Returns Err if any problems appear. 
+    pub fn new_from_serialized(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        //open the file
+        let file = std::fs::File::open(path)?;
+
+        //deserialze
+        let replay = serde_cbor::from_reader(file)?;
+
+        Ok(replay)
+    }
+
+    /// Returns the files of all saved replays, the most recently started game first. 
+    pub fn list_files() -> Vec<PathBuf> {
+        let Ok(entries) = std::fs::read_dir(REPLAY_DIRECTORY) else {return Vec::new();};
+
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "dat"))
+            .collect();
+        //the file names start with the time the game was started, so they sort by it
+        files.sort_unstable_by(|a, b| b.cmp(a));
+        files
+    }
+}
+
+/// Formats seconds since the unix epoch as a UTC date and time which sorts chronologically and can be 
+/// part of a file name, e.g. 2025-01-31_18-05-09. 
+fn format_timestamp(seconds: u64) -> String {
+    //the civil from days algorithm, counting in eras of 400 years starting on the 1st of March 0000
+    let days = seconds / SECONDS_PER_DAY + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {shifted_month + 3} else {shifted_month - 9};
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+
+    let second_of_day = seconds % SECONDS_PER_DAY;
+    format!(
+        "{year:04}-{month:02}-{day:02}_{:02}-{:02}-{:02}", 
+        second_of_day / 3600, 
+        second_of_day / 60 % 60, 
+        second_of_day % 60,
+    )
+}
+
+/// The state of a replay being played back. Boards with this component are driven by the replay 
+/// instead of the players input. 
+#[derive(Component, Debug)]
+pub struct Playback {
+    pub replay: Replay,
+    /// The index of the next action to be performed. 
+    index: usize,
+    /// The time of the replay up to which all actions have been performed. 
+    clock: Duration,
+    /// The time of the replay the playback should be at. 
+    target: Duration,
+    length: Duration,
+    pub speed: f32,
+    pub paused: bool,
+}
+
+impl Playback {
+    /// Starts playing back the given replay from the beginning. 
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            length: replay.length(),
+            replay,
+            index: 0,
+            clock: Duration::ZERO,
+            target: Duration::ZERO,
+            speed: 1.0,
+            paused: false,
+        }
+    }
+
+    /// Returns the time of the replay the playback is at. 
+    pub fn position(&self) -> Duration {
+        self.target
+    }
+
+    /// Returns the time from the start of the replay until its last action. 
+    pub fn length(&self) -> Duration {
+        self.length
+    }
+
+    /// Doubles the playback speed, up to 8 times the original speed. 
+    pub fn speed_up(&mut self) {
+        self.speed = (self.speed * 2.0).min(PLAYBACK_MAX_SPEED);
+    }
+
+    /// Halves the playback speed, down to a quarter of the original speed. 
+    pub fn slow_down(&mut self) {
+        self.speed = (self.speed / 2.0).max(PLAYBACK_MIN_SPEED);
+    }
+
+    /// Moves the playback to the given time of the replay. Returns true if the time lies before the 
+    /// actions that have already been performed, in which case the game has to be recreated with 
+    /// [Replay::game] as the actions are performed again from the beginning. 
+    pub fn seek(&mut self, target: Duration) -> bool {
+        self.target = target.min(self.length);
+
+        let rewind = self.target < self.clock;
+        if rewind {
+            self.index = 0;
+            self.clock = Duration::ZERO;
+        }
+        rewind
+    }
+
+    /// Advances the playback by the given real time, scaled by the playback speed unless paused, and 
+    /// returns all actions up to the new position of the playback. 
+    pub fn advance(&mut self, delta: Duration) -> Vec<ReplayAction> {
+        if !self.paused {
+            self.target = (self.target + delta.mul_f32(self.speed)).min(self.length);
+        }
+
+        let mut actions = Vec::new();
+        while let Some(action) = self.replay.actions.get(self.index) {
+            if let ReplayAction::Tick(delta) = action {
+                if self.clock + *delta > self.target {
+                    break;
+                }
+                self.clock += *delta;
+            }
+
+            actions.push(*action);
+            self.index += 1;
+        }
+
+        actions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::model::GameEvent;
+
+    use super::*;
+
+    const FRAME: Duration = Duration::from_millis(16);
+
+    /// Applies the action to the game and records it like a board played on this device does. Returns 
+    /// whether the game is over. 
+    fn play(game: &mut Game, action: ReplayAction) -> bool {
+        action.apply(&mut game.tetris);
+        let events: Vec<GameEvent> = game.tetris.drain_events().collect();
+        let locked = events.iter().any(|event| matches!(event, GameEvent::Locked(_)));
+        game.replay.record(action, locked);
+        events.iter().any(|event| matches!(event, GameEvent::GameOver(_)))
+    }
+
+    /// Creates a replay which ticks once per frame and shifts, spins and drops the pieces in between. 
+    fn recorded_game(frames: u32) -> Game {
+        let instructions = [
+            TetrisInstruction::Left, 
+            TetrisInstruction::RotateClock, 
+            TetrisInstruction::Right, 
+            TetrisInstruction::Right, 
+            TetrisInstruction::Store,
+            TetrisInstruction::RotateCounter, 
+            TetrisInstruction::FullDrop,
+        ];
+
+        let mut game = Game::new(42, TetrisRules::default(), Vec::new());
+        for frame in 0..frames {
+            let mut actions = vec![ReplayAction::Tick(FRAME)];
+            if frame % 20 == 0 {
+                actions.push(ReplayAction::Gravity);
+            }
+            if frame % 50 == 0 {
+                actions.push(ReplayAction::Instruction(instructions[(frame / 50) as usize % instructions.len()]));
+            }
+
+            for action in actions {
+                if play(&mut game, action) {
+                    return game;
+                }
+            }
+        }
+        game
+    }
+
+    /// Applies all actions of the replay to a new game. 
+    fn play_back(replay: &Replay) -> Game {
+        let mut game = replay.game();
+        for action in &replay.actions {
+            action.apply(&mut game.tetris);
+        }
+        game
+    }
+
+    #[test]
+    fn playing_back_a_replay_reproduces_the_game() {
+        let game = recorded_game(5000);
+        let played_back = play_back(&game.replay);
+
+        assert_eq!(played_back.tetris.get_block_list(), game.tetris.get_block_list());
+        assert_eq!(played_back.tetris.get_active_piece_list(), game.tetris.get_active_piece_list());
+        assert_eq!(played_back.tetris.score(), game.tetris.score());
+        assert!(game.tetris.score().points > 0);
+    }
+
+    #[test]
+    fn consecutive_ticks_are_merged() {
+        let game = recorded_game(1000);
+
+        //a frame rate independent replay holds roughly one tick per other action
+        let ticks = game.replay.actions.iter().filter(|action| matches!(action, ReplayAction::Tick(_))).count();
+        let others = game.replay.actions.len() - ticks;
+        assert!(ticks <= 2 * others + 1, "{ticks} ticks for {others} other actions");
+        assert_eq!(game.replay.length(), FRAME * 1000);
+    }
+
+    #[test]
+    fn seeking_backwards_rewinds_the_playback() {
+        let mut playback = Playback::new(recorded_game(1000).replay);
+        playback.advance(Duration::from_secs(10));
+        assert!(playback.index > 0);
+
+        assert!(!playback.seek(Duration::from_secs(12)));
+        assert!(playback.index > 0);
+
+        assert!(playback.seek(Duration::from_secs(5)));
+        assert_eq!(playback.index, 0);
+        assert_eq!(playback.clock, Duration::ZERO);
+        assert_eq!(playback.position(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn advancing_stops_before_ticks_past_the_target() {
+        let mut replay = Replay::new(0, TetrisRules::default(), Vec::new());
+        replay.actions = vec![
+            ReplayAction::Tick(Duration::from_millis(100)),
+            ReplayAction::Gravity,
+            ReplayAction::Tick(Duration::from_millis(100)),
+            ReplayAction::Gravity,
+        ];
+        let mut playback = Playback::new(replay);
+
+        let actions = playback.advance(Duration::from_millis(150));
+        assert_eq!(actions, [ReplayAction::Tick(Duration::from_millis(100)), ReplayAction::Gravity]);
+        assert_eq!(playback.clock, Duration::from_millis(100));
+
+        //at double speed the remaining 50ms of real time reach the end of the replay
+        playback.speed_up();
+        let actions = playback.advance(Duration::from_millis(25));
+        assert_eq!(actions, [ReplayAction::Tick(Duration::from_millis(100)), ReplayAction::Gravity]);
+        assert_eq!(playback.position(), playback.length());
+    }
+
+    #[test]
+    fn playback_speed_stays_within_its_limits() {
+        let mut playback = Playback::new(Replay::new(0, TetrisRules::default(), Vec::new()));
+        for _ in 0..10 {
+            playback.speed_up();
+        }
+        assert_eq!(playback.speed, PLAYBACK_MAX_SPEED);
+        for _ in 0..10 {
+            playback.slow_down();
+        }
+        assert_eq!(playback.speed, PLAYBACK_MIN_SPEED);
+    }
+
+    #[test]
+    fn timestamps_are_formatted_as_utc_date_and_time() {
+        assert_eq!(format_timestamp(0), "1970-01-01_00-00-00");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29_00-00-00");
+        assert_eq!(format_timestamp(1_000_000_000), "2001-09-09_01-46-40");
+    }
+}
@@ -3,39 +3,70 @@ use std::time::Duration;
 
 use bevy::audio::Volume;
 use bevy::color::palettes::css::BLACK;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
+use rand::Rng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
 
 use crate::engine;
 use crate::engine::line_stuff::LineListIndex;
 use crate::engine::line_stuff::LineMaterial;
 use crate::engine::model::CellStatus;
+use crate::engine::model::GameEvent;
+use crate::engine::model::TETRIS_FIELD_DEFAULT_HEIGHT;
+use crate::engine::model::TetrisRules;
+use crate::engine::model::Tetromino;
+use crate::engine::model::randomizer::FixedSequence;
+use crate::engine::model::randomizer::RandomizerKind;
+use crate::engine::replay::Replay;
+use crate::engine::replay::ReplayAction;
+use crate::engine::replay::Playback;
+use crate::ui::BindingInput;
+use crate::ui::Handling;
+use crate::ui::KeyBinds;
 use crate::ui::Settings;
 use crate::ui::TetrisInstruction;
 use crate::ui::WaitingForNewKeyBind;
 
-const ONE_LINE_SCORE: u32 = 100;
-const TWO_LINE_SCORE: u32 = 300;
-const THREE_LINE_SCORE: u32 = 500;
-const FOUR_LINE_SCORE: u32 = 800;
-const SLOW_DROP_SCORE: u32 = 1;
-const FAST_DROP_SCORE: u32 = 2;
-
 const BASE_DROP_DURATION_SECS: f64 = 1.0;
-const DIFFICULTY: u32 = 1;  //TODO should always be 1
+const CAMERA_BASE_DISTANCE: f32 = 25.0;
+const PREVIEW_SLOT_HEIGHT: i32 = 3;
+const FIXED_SEQUENCE_PATH: &str = "data/sequence.txt";
+const REPLAY_SEEK_STEP: Duration = Duration::from_secs(5);
+const BOARD_SIDE_WIDTH: f32 = 5.0;
+const BOARD_GAP: f32 = 2.0;
+const CAMERA_BASE_HALF_WIDTH: f32 = 16.0;
+const SAVED_GAME_PATH: &str = "data/saved_game.dat";
+const QUICK_RESTART_HOLD: Duration = Duration::from_millis(500);
+//the pieces are dealt from stream 0 of the seed, the holes of the garbage and cheese rows from their own
+const GARBAGE_RNG_STREAM: u64 = 1;
+const CHEESE_RNG_STREAM: u64 = 2;
 
 pub struct ScenePlugin;
 
 impl Plugin for ScenePlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(engine::line_stuff::LineStuffPlugin);
+        app.add_event::<LinesCleared>();
+        app.add_event::<PieceLocked>();
+        app.add_event::<LevelUp>();
+        app.add_event::<GameOver>();
+        app.add_event::<Paused>();
+        app.add_event::<Resumed>();
+        app.add_event::<HoldUsed>();
         app.add_systems(Startup, setup);
-        app.add_systems(Update, display_game_state);
-        app.add_systems(Update, update_game_state);
-        app.add_systems(Update, display_next_piece);
-        app.add_systems(Update, display_stored_piece);
-        app.add_systems(Update, display_ghost_piece);
+        app.add_systems(Update, capture_key_bind);
+        app.add_systems(Update, queue_local_input.before(apply_input_queues));
+        app.add_systems(Update, queue_replay_input.before(apply_input_queues));
+        app.add_systems(Update, apply_input_queues);
+        app.add_systems(Update, spawn_board_display);
+        app.add_systems(Update, display_cell_grids);
+        app.add_systems(Update, display_board_frames);
         app.add_systems(Update, update_audio);
         app.add_systems(Update, manage_pause);
+        app.add_systems(Update, handle_meta_instructions);
+        app.add_systems(Last, save_game_on_exit);
     }
 }
 
@@ -44,7 +75,8 @@ fn setup(mut commands: Commands,
          mut materials: ResMut<Assets<StandardMaterial>>, 
          mut materials_line: ResMut<Assets<LineMaterial>>,
          mut clear_color: ResMut<ClearColor>,
-         asset_server: Res<AssetServer>
+         asset_server: Res<AssetServer>,
+         settings: Res<Settings>,
         ) {
     //colorful materials for each block color
     let mut material_map = HashMap::new();
@@ -55,6 +87,7 @@ fn setup(mut commands: Commands,
     material_map.insert(CellStatus::Red, materials.add(Color::srgb(1.0, 0.0, 0.0)));
     material_map.insert(CellStatus::Blue, materials.add(Color::srgb(0.0, 0.0, 1.0)));
     material_map.insert(CellStatus::Orange, materials.add(Color::srgb(1.0, 0.5, 0.0)));
+    material_map.insert(CellStatus::Garbage, materials.add(Color::srgb(0.5, 0.5, 0.5)));
 
     commands.insert_resource(MaterialsHandle(material_map));
 
@@ -69,24 +102,20 @@ fn setup(mut commands: Commands,
     //load mesh of line cube
     commands.insert_resource(LineCubeHandle(line_cube_handle.clone()));
 
-    //tetris model
-    commands.spawn((
-        Game::default(),
-    ));
-
-    //score of the game
-    commands.insert_resource(GameScore::default());
-
-    //timer that repeatedly drops active tetromino
-    commands.insert_resource(DropTimer(Timer::from_seconds(2.0, TimerMode::Repeating)));
-
-    //flag that is set to recolor existing cubes
-    commands.insert_resource(RecolorCubes(false));
+    //the tetris model of the last game if it has been left unfinished, otherwise of a new game
+    let saved_game = SavedGame::new_from_serialized().ok();
+    let resumable = saved_game.is_some();
+    if let Some(saved_game) = saved_game {
+        let board = spawn_board(&mut commands, saved_game.game, 0, 1);
+        commands.entity(board).insert((saved_game.score, saved_game.drop_timer));
+    } else {
+        spawn_boards(&mut commands, &settings);
+    }
 
-    //camera
+    //camera, its distance is adjusted to the field size by display_board_frames
     commands.spawn((
         Camera3d::default(),
-        Transform::from_xyz(0.0, 0.0, 25.0).looking_at(Vec3::ZERO, Vec3::Y),
+        Transform::from_xyz(0.0, 0.0, CAMERA_BASE_DISTANCE).looking_at(Vec3::ZERO, Vec3::Y),
     ));
 
     //make background black
@@ -101,27 +130,6 @@ fn setup(mut commands: Commands,
         Transform::from_xyz(0.0, 0.0, 6.0),
     ));
 
-    //line cube
-    commands.spawn((
-        Mesh3d(line_cube_handle.clone()),
-        MeshMaterial3d(materials_line.add(LineMaterial{color: LinearRgba::WHITE})),
-        Transform::from_scale(Vec3::new(5.0, 10.0, 0.5)),
-    ));
-
-    //next piece line cube
-    commands.spawn((
-        Mesh3d(line_cube_handle.clone()),
-        MeshMaterial3d(materials_line.add(LineMaterial{color: LinearRgba::WHITE})),
-        Transform::from_scale(Vec3::new(2.0, 1.0, 0.5)).with_translation(Vec3::new(12.0, 16.0, 0.0) - Vec3::new(4.0, 10.5, 0.0)),
-    ));
-
-    //stored piece line cube
-    commands.spawn((
-        Mesh3d(line_cube_handle.clone()),
-        MeshMaterial3d(materials_line.add(LineMaterial{color: LinearRgba::WHITE})),
-        Transform::from_scale(Vec3::new(2.0, 1.0, 0.5)).with_translation(Vec3::new(12.0, 12.0, 0.0) - Vec3::new(4.0, 10.5, 0.0)),
-    ));
-
     //start music
     commands.spawn((
         AudioPlayer::new(asset_server.load("music/Tetris.ogg")),
@@ -132,7 +140,13 @@ fn setup(mut commands: Commands,
     ));
 
     //add a struct which will prematurely end all functions working with a game overed game
-    commands.insert_resource(IsAppRunning(AppState::Running));
+    if resumable {
+        //let the player decide whether to resume the last game
+        commands.insert_resource(IsAppRunning(AppState::Resumable));
+        commands.spawn(crate::ui::generate_resume_screen());
+    } else {
+        commands.insert_resource(IsAppRunning(AppState::Running));
+    }
 
     //center dividing line
     // commands.spawn((
@@ -145,265 +159,407 @@ fn setup(mut commands: Commands,
     // ));
 }
 
-/// A wrapper struct for the Tetris model. 
-#[derive(Clone, Component, Debug, Default)]
-pub struct Game {
-    pub tetris: engine::model::Tetris<rand::rngs::OsRng>,
+/// Creates a new game with the rules of the settings. The game is dealt from the seed of the settings, 
+/// or from a random seed if none is set. The fixed sequence randomizer deals the pieces listed by their 
+/// letters in the data/sequence.txt file, other characters are ignored. 
+pub(crate) fn new_game(settings: &Settings) -> Game {
+    let rules = settings.rules;
+    let seed = settings.seed.unwrap_or_else(rand::random);
+
+    let mut sequence = Vec::new();
+    if rules.randomizer == RandomizerKind::Fixed {
+        match std::fs::read_to_string(FIXED_SEQUENCE_PATH) {
+            Ok(text) => sequence.extend(text.chars().filter_map(Tetromino::from_letter)),
+            Err(err) => warn!("Could not read the fixed piece sequence from {FIXED_SEQUENCE_PATH}. Error: {}", err),
+        }
+    }
+
+    Game::new(seed, rules, sequence)
 }
 
-/// Adds new colored cubes for every new cell and removes all cubes for cells that have disappeared. 
-/// Manages only the main playfield. 
-fn display_game_state(
-        mut commands: Commands, 
-        game_query: Query<&Game>, 
-        mut cubes_query: Query<(Entity, &CellPosition, &mut MeshMaterial3d<StandardMaterial>), With<MainPixelMarker>>,
-        cube_handle: Res<CubeHandle>,
-        material_handles: Res<MaterialsHandle>,
-        mut update_cube_color: ResMut<RecolorCubes>,
-        running: Res<IsAppRunning>,
-    ) {
-    if !(running.0 == AppState::Running) {
-        return;
-    }
-    if game_query.is_empty() {
-        error!("Game is missing!");
-        return;
-    }
-    let game = game_query.into_iter().next().unwrap();
-    
-    //main cubes
+/// A snapshot of everything needed to continue an unfinished game, including the replay recorded so 
+/// far. Its consecutive ticks are merged, so idle time does not grow the save, see [Replay::record]. 
+#[derive(Serialize, Deserialize)]
+struct SavedGame {
+    game: Game,
+    score: GameScore,
+    drop_timer: DropTimer,
+}
 
-    //get all the cubes the system is currently displaying
-    let mut existing_cubes = cubes_query
-        .iter_mut()
-        .fold(HashMap::new(), |mut map, (entity, pos, material)| {map.insert(pos, (entity, material)); map});
+impl SavedGame {
+    /// Tries to save the game to the data/saved_game.dat file. Returns Err 
+    /// if any problems appear. 
+    fn write_to_file(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let _ = std::fs::create_dir("./data");
 
-    //get all the positions where cubes should be. If one is missing, spawn it
-    for (cell, x, y) in game.tetris.get_block_list() {
-        let pos = CellPosition::new(x as i32, y as i32);
+        //get a file 
+        let file = std::fs::File::create(SAVED_GAME_PATH)?;
 
-        if let Some((_, material)) = &mut existing_cubes.remove(&pos) {
-            //if necessary flag is set, re assign every material. 
-            // this prevents a bug where immediately dropping a piece will mis-color some cubes of the following piece
-            if update_cube_color.0 {
-                material.0 = material_handles.0[&cell].clone();
-            }
-        } else {
-            //spawn new cube
-            let material_handle = &material_handles.0[&cell];
+        //serialize and write to file
+        serde_cbor::to_writer(file, &self)?;
 
-            commands.spawn((
-                Mesh3d(cube_handle.0.clone()),
-                MeshMaterial3d(material_handle.clone()),
-                Transform::from_translation(Vec3::from(pos) - Vec3::new(4.5, 9.5, 0.0)),
-                pos,
-                MainPixelMarker,
-            ));
-        }
+        Ok(())
     }
 
-    //all remaining cubes are at positions where nothing should be, remove them
-    for (_, (entity, _)) in existing_cubes.into_iter() {
-        commands.entity(entity).despawn();
+    /// Reads the game from the data/saved_game.dat file. Returns Err if
+    /// any problems appear. 
+    fn new_from_serialized() -> Result<Self, Box<dyn std::error::Error>> {
+        //open the file
+        let file = std::fs::File::open(SAVED_GAME_PATH)?;
+
+        //deserialze 
+        let saved_game = serde_cbor::from_reader(file)?;
+
+        Ok(saved_game)
     }
+}
 
-    update_cube_color.0 = false;
+/// Saves the game so it can be resumed after the next start, along with its replay so far. Only games 
+/// with a single board are saved, versus games are skipped. Replays being played back are not saved 
+/// either, as they can simply be watched again. 
+fn save_game(board_query: &Query<(&Game, &GameScore, &DropTimer, Has<Playback>)>) {
+    if board_query.iter().count() > 1 {
+        return;
+    }
+    let Ok((game, score, timer, false)) = board_query.single() else {return;};
+
+    let saved_game = SavedGame {
+        game: game.clone(),
+        score: score.clone(),
+        drop_timer: timer.clone(),
+    };
+    if let Err(err) = saved_game.write_to_file() {
+        error!("Could not save the game. Error: {}", err);
+    }
+    if let Err(err) = game.replay.write_to_file() {
+        error!("Could not save the replay. Error: {}", err);
+    }
 }
 
-/// Adds new colored cubes for every new cell and removes all cubes for cells that have disappeared. 
-/// Manages only the next piece. 
-fn display_next_piece(
-    mut commands: Commands, 
-    game_query: Query<&Game>, 
-    mut next_cubes_query: Query<(Entity, &CellPosition, &mut MeshMaterial3d<StandardMaterial>), With<NextPixelMarker>>,
-    cube_handle: Res<CubeHandle>,
-    material_handles: Res<MaterialsHandle>,
+/// Saves the game when the app is closed, unless the game is already over. 
+fn save_game_on_exit(
+    mut exit_reader: EventReader<AppExit>,
+    board_query: Query<(&Game, &GameScore, &DropTimer, Has<Playback>)>,
     running: Res<IsAppRunning>,
 ) {
-    if !(running.0 == AppState::Running) {
+    if exit_reader.read().next().is_none() || running.0 == AppState::GameOver {
         return;
     }
 
-    if game_query.is_empty() {
-        error!("Game is missing!");
-        return;
-    }
-    let game = game_query.into_iter().next().unwrap();
-    
-    //next piece cubes
+    save_game(&board_query);
+}
+
+/// The random number generator games are dealt with. It is portable, so the same seed deals the same 
+/// pieces on every platform. 
+pub type GameRng = rand_chacha::ChaCha8Rng;
 
-    //get all the cubes the system is currently displaying
-    let mut existing_next_cubes = next_cubes_query
-        .iter_mut()
-        .fold(HashMap::new(), |mut map, (entity, pos, material)| {map.insert(pos, (entity, material)); map});
+/// A wrapper struct for the Tetris model along with the seed it was created from and the replay 
+/// recorded while playing it. 
+#[derive(Clone, Component, Debug, Serialize, Deserialize)]
+pub struct Game {
+    pub tetris: engine::model::Tetris<GameRng>,
+    pub seed: u64,
+    pub replay: Replay,
+}
 
-    //get all the positions where cubes should be. If one is missing, spawn it
-    for (cell, x, y) in game.tetris.get_next_block_list() {
-        let pos = CellPosition::new(x as i32, y as i32);
+impl Game {
+    /// Creates a new game dealt from the given seed, starting with the cheese rows of the rules. The 
+    /// sequence is only dealt by the fixed sequence randomizer. 
+    pub fn new(seed: u64, rules: TetrisRules, sequence: Vec<Tetromino>) -> Self {
+        let rng = GameRng::seed_from_u64(seed);
 
-        if let Some((_, material)) = &mut existing_next_cubes.remove(&pos) {
-            //if necessary flag is set, re assign every material. 
-            material.0 = material_handles.0[&cell].clone();
+        let mut tetris = if rules.randomizer != RandomizerKind::Fixed {
+            engine::model::Tetris::new(rng, rules)
         } else {
-            //spawn new cube
-            let material_handle = &material_handles.0[&cell];
+            engine::model::Tetris::with_randomizer(rng, rules, Box::new(FixedSequence::new(sequence.clone())))
+        };
+        let mut cheese_rng = GameRng::seed_from_u64(seed);
+        cheese_rng.set_stream(CHEESE_RNG_STREAM);
+        tetris.add_cheese_rows(&mut cheese_rng);
 
-            commands.spawn((
-                Mesh3d(cube_handle.0.clone()),
-                MeshMaterial3d(material_handle.clone()),
-                Transform::from_translation(Vec3::from(pos) - Vec3::new(4.5, 10.0, 0.0) + Vec3::new(11.0, 15.0, 0.0)),
-                pos,
-                NextPixelMarker,
-            ));
+        Self {
+            tetris,
+            seed,
+            replay: Replay::new(seed, rules, sequence),
         }
     }
-    //all remaining cubes are at positions where nothing should be, remove them
-    for (_, (entity, _)) in existing_next_cubes.into_iter() {
-        commands.entity(entity).despawn();
-    }
 }
 
-/// Adds new colored cubes for every new cell and removes all cubes for cells that have disappeared. 
-/// Manages only the stored piece. 
-fn display_stored_piece(
-    mut commands: Commands, 
-    game_query: Query<&Game>, 
-    mut stored_cubes_query: Query<(Entity, &CellPosition, &mut MeshMaterial3d<StandardMaterial>), With<StoredPixelMarker>>,
-    cube_handle: Res<CubeHandle>,
-    material_handles: Res<MaterialsHandle>,
-    running: Res<IsAppRunning>,
-) {
-    if !(running.0 == AppState::Running) {
-        return;
+/// Whether a game is played alone or by two players against each other. 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameMode {
+    #[default]
+    Single,
+    /// Two players play side by side with the same pieces. Lines cleared by one player send garbage to 
+    /// the other and the first player to top out loses. 
+    Versus,
+}
+
+impl GameMode {
+    /// Returns an array of all game modes. 
+    pub fn all_game_mode_array() -> [Self; 2] {
+        [
+            Self::Single,
+            Self::Versus,
+        ]
     }
 
-    if game_query.is_empty() {
-        error!("Game is missing!");
-        return;
+    /// Returns the number of boards played in this mode. 
+    pub fn players(self) -> usize {
+        match self {
+            Self::Single => 1,
+            Self::Versus => 2,
+        }
     }
-    let game = game_query.into_iter().next().unwrap();
-    
-    //next piece cubes
+}
 
-    //get all the cubes the system is currently displaying
-    let mut existing_stored_cubes = stored_cubes_query
-        .iter_mut()
-        .fold(HashMap::new(), |mut map, (entity, pos, material)| {map.insert(pos, (entity, material)); map});
+/// Marks the entity holding the [Game] of one player, next to the score, drop timer and recolor flag 
+/// of that game. Its transform places the center of the playfield, all cubes and frames displaying 
+/// the board are its children. 
+#[derive(Component, Clone, Copy, Debug)]
+#[require(Transform, Visibility)]
+pub struct Board {
+    pub player: usize,
+}
 
-    //get all the positions where cubes should be. If one is missing, spawn it
-    for (cell, x, y) in game.tetris.get_stored_block_list() {
-        let pos = CellPosition::new(x as i32, y as i32);
+/// Spawns a board for every player of the game mode of the settings. All boards play the same new game. 
+/// The saved game is deleted, as it is replaced by the new one. 
+pub(crate) fn spawn_boards(commands: &mut Commands, settings: &Settings) {
+    let _ = std::fs::remove_file(SAVED_GAME_PATH);
 
-        if let Some((_, material)) = &mut existing_stored_cubes.remove(&pos) {
-            //if necessary flag is set, re assign every material. 
-            material.0 = material_handles.0[&cell].clone();
-        } else {
-            //spawn new cube
-            let material_handle = &material_handles.0[&cell];
+    let game = new_game(settings);
+    let players = settings.game_mode.players();
+    for player in 0..players {
+        spawn_board(commands, game.clone(), player, players);
+    }
+}
+
+/// Spawns the board of the given player, placed next to the boards of the other players. Returns 
+/// the board entity. 
+pub(crate) fn spawn_board(commands: &mut Commands, game: Game, player: usize, players: usize) -> Entity {
+    //every board is as wide as its field and the stored and next piece frames to its sides
+    let board_width = game.tetris.field_width() as f32 + 2.0 * BOARD_SIDE_WIDTH + BOARD_GAP;
+    let x = (player as f32 - (players as f32 - 1.0) / 2.0) * board_width;
+
+    //a separate stream, so the holes do not follow the pieces dealt from the same seed
+    let mut garbage_rng = GameRng::seed_from_u64(game.seed);
+    garbage_rng.set_stream(GARBAGE_RNG_STREAM);
 
+    commands.spawn((
+        game,
+        Board {player},
+        Transform::from_xyz(x, 0.0, 0.0),
+        GameScore::default(),
+        DropTimer::default(),
+        AutoShift::default(),
+        InputQueue::default(),
+        RestartHold::default(),
+        GarbageRng(garbage_rng),
+    )).id()
+}
+
+/// Spawns the cell grids displaying the playfield, the ghost piece, the next and the stored piece of 
+/// every new board, as well as the line cubes framing them. The frames are adjusted to the board by 
+/// display_board_frames. 
+fn spawn_board_display(
+    mut commands: Commands,
+    board_query: Query<(Entity, &Game), Added<Board>>,
+    line_cube_handle: Res<LineCubeHandle>,
+    mut materials_line: ResMut<Assets<LineMaterial>>,
+) {
+    for (board, game) in board_query {
+        let grids = [
+            (CellSource::Field, -field_offset(&game.tetris), CellStyle::Solid),
+            (CellSource::Ghost, -field_offset(&game.tetris), CellStyle::Outline),
+            (CellSource::Next, next_piece_offset(&game.tetris), CellStyle::Solid),
+            (CellSource::Stored, stored_piece_offset(&game.tetris), CellStyle::Solid),
+        ];
+        for (source, origin, style) in grids {
             commands.spawn((
-                Mesh3d(cube_handle.0.clone()),
-                MeshMaterial3d(material_handle.clone()),
-                Transform::from_translation(Vec3::from(pos) - Vec3::new(4.5, 10.0, 0.0) + Vec3::new(11.0, 11.0, 0.0)),
-                pos,
-                StoredPixelMarker,
+                CellGrid::new(source, origin, style),
+                ChildOf(board),
+            ));
+        }
+
+        for frame in [BoardFrame::Field, BoardFrame::Next, BoardFrame::Stored] {
+            commands.spawn((
+                Mesh3d(line_cube_handle.0.clone()),
+                MeshMaterial3d(materials_line.add(LineMaterial{color: LinearRgba::WHITE})),
+                Transform::IDENTITY,
+                frame,
+                ChildOf(board),
             ));
         }
-    }
-    //all remaining cubes are at positions where nothing should be, remove them
-    for (_, (entity, _)) in existing_stored_cubes.into_iter() {
-        commands.entity(entity).despawn();
     }
 }
 
-/// Adds new line cubes for every new cell and removes all line cubes for cells that have disappeared. 
-/// Manages only the gost piece. 
-fn display_ghost_piece(
+/// Brings every cell grid up to date with the game of its board. A grid is only updated if the game 
+/// has changed, the grid is new or the settings have changed. Cubes are reused between updates, cubes 
+/// which are not needed anymore are hidden instead of despawned. 
+fn display_cell_grids(
     mut commands: Commands, 
-    game_query: Query<&Game>, 
-    mut ghost_cubes_query: Query<(Entity, &CellPosition, &mut MeshMaterial3d<LineMaterial>), With<GhostPixelMarker>>,
-    mut line_cube_handle: ResMut<LineCubeHandle>,
+    board_query: Query<Ref<Game>, With<Board>>, 
+    mut grid_query: Query<(Entity, &ChildOf, &mut CellGrid, &mut Transform), Without<GridCube>>,
+    mut cube_query: Query<(&mut Transform, &mut Visibility, Option<&mut MeshMaterial3d<StandardMaterial>>), With<GridCube>>,
+    cube_handle: Res<CubeHandle>,
+    material_handles: Res<MaterialsHandle>,
+    line_cube_handle: Res<LineCubeHandle>,
     line_material_handle: Res<LineMaterialHandle>,
-    running: Res<IsAppRunning>,
-
-    mut meshes: ResMut<Assets<Mesh>>, 
+    settings: Res<Settings>,
 ) {
-    if !(running.0 == AppState::Running) {
-        return;
-    }
-
-    let game = game_query.into_iter().next().unwrap();
-    
-    //next piece cubes
-
-    //get all the cubes the system is currently displaying
-    let mut existing_ghost_cubes = ghost_cubes_query
-        .iter_mut()
-        .fold(HashMap::new(), |mut map, (entity, pos, material)| {map.insert(pos, (entity, material)); map});
+    for (grid_entity, child_of, mut grid, mut grid_transform) in &mut grid_query {
+        let Ok(game) = board_query.get(child_of.parent()) else {continue;};
+        if !game.is_changed() && !grid.is_added() && !settings.is_changed() {
+            continue;
+        }
 
-    //get all the positions where cubes should be. If one is missing, spawn it
-    for (x, y) in game.tetris.get_ghost_piece_list() {
-        let pos = CellPosition::new(x as i32, y as i32);
+        *grid_transform = Transform::from_translation(grid.origin).with_scale(Vec3::splat(grid.cell_size));
+
+        let cells = grid.source.cells(&game.tetris, &settings);
+        for (index, (cell, pos)) in cells.iter().enumerate() {
+            //reuse a cube of an earlier update if there is one left
+            if let Some(cube) = grid.cubes.get(index) {
+                let Ok((mut transform, mut visibility, material)) = cube_query.get_mut(*cube) else {continue;};
+                transform.translation = Vec3::from(*pos);
+                *visibility = Visibility::Inherited;
+                if let Some(mut material) = material {
+                    material.0 = material_handles.0[cell].clone();
+                }
+                continue;
+            }
 
-        if let Some((_, material)) = &mut existing_ghost_cubes.remove(&pos) {
-            material.0 = line_material_handle.0.clone();
-        } else {
             //spawn new cube
-            let material_handle = &line_material_handle.0;
+            let cube = match grid.style {
+                CellStyle::Solid => {
+                    commands.spawn((
+                        Mesh3d(cube_handle.0.clone()),
+                        MeshMaterial3d(material_handles.0[cell].clone()),
+                        Transform::from_translation(Vec3::from(*pos)),
+                        GridCube,
+                        ChildOf(grid_entity),
+                    )).id()
+                }
+                CellStyle::Outline => {
+                    commands.spawn((
+                        Mesh3d(line_cube_handle.0.clone()),
+                        MeshMaterial3d(line_material_handle.0.clone()),
+                        Transform::from_scale(Vec3::new(0.5, 0.5, 0.5))
+                            .with_translation(Vec3::from(*pos)),
+                        GridCube,
+                        ChildOf(grid_entity),
+                    )).id()
+                }
+            };
+            grid.cubes.push(cube);
+        }
 
-            if meshes.get(&line_cube_handle.0.clone()).is_none() {  //TODO THIS BUG IS SHIT
-                warn!("Line Cube Mesh has been unloaded. Reloading it");
-                line_cube_handle.0 = meshes.add(LineListIndex::cube());
+        //all remaining cubes are not needed right now, hide them
+        for cube in grid.cubes.iter().skip(cells.len()) {
+            if let Ok((_, mut visibility, _)) = cube_query.get_mut(*cube) {
+                *visibility = Visibility::Hidden;
             }
-
-            commands.spawn((
-                Mesh3d(line_cube_handle.0.clone()),
-                //Mesh3d(meshes.add(LineListIndex::cube())),
-                MeshMaterial3d(material_handle.clone()),
-                Transform::from_scale(Vec3::new(0.5, 0.5, 0.5))
-                    .with_translation(Vec3::from(pos) - Vec3::new(4.5, 9.5, 0.0)),
-                pos,
-                GhostPixelMarker,
-            ));
         }
     }
-    //all remaining cubes are at positions where nothing should be, remove them
-    for (_, (entity, _)) in existing_ghost_cubes.into_iter() {
-        commands.entity(entity).despawn();
+}
+
+/// Scales and positions the line cubes framing the playfield, the next and the stored piece of every 
+/// board to the size of its playfield and the number of shown next pieces. Also moves the camera back 
+/// far enough to see all boards entirely. 
+fn display_board_frames(
+    board_query: Query<(&Game, &Transform), (With<Board>, Without<BoardFrame>)>,
+    mut frames_query: Query<(&BoardFrame, &ChildOf, &mut Transform), (Without<Camera3d>, Without<Board>)>,
+    mut camera_query: Query<&mut Transform, (With<Camera3d>, Without<Board>)>,
+    settings: Res<Settings>,
+) {
+    //every preview slot is two cells high with one cell of space in between
+    let preview_count = settings.preview_count.clamp(1, engine::model::MAX_PREVIEW_COUNT);
+    let half_next_height = (preview_count as f32 * PREVIEW_SLOT_HEIGHT as f32 - 1.0) / 2.0;
+
+    for (frame, child_of, mut transform) in &mut frames_query {
+        let Ok((game, _)) = board_query.get(child_of.parent()) else {continue;};
+        let half_width = game.tetris.field_width() as f32 / 2.0;
+        let half_height = game.tetris.field_height() as f32 / 2.0;
+
+        *transform = match frame {
+            BoardFrame::Field => {
+                Transform::from_scale(Vec3::new(half_width, half_height, 0.5))
+            }
+            BoardFrame::Next => {
+                Transform::from_scale(Vec3::new(2.0, half_next_height, 0.5))
+                    .with_translation(Vec3::new(half_width + 3.0, half_height - 3.5 - half_next_height, 0.0))
+            }
+            BoardFrame::Stored => {
+                Transform::from_scale(Vec3::new(2.0, 1.0, 0.5))
+                    .with_translation(Vec3::new(-half_width - 3.0, half_height - 4.5, 0.0))
+            }
+        };
+    }
+
+    let Ok(mut camera_transform) = camera_query.single_mut() else {return;};
+    let mut visible_half_width: f32 = 0.0;
+    let mut visible_half_height: f32 = 0.0;
+    for (game, board) in board_query {
+        let half_width = game.tetris.field_width() as f32 / 2.0;
+        let half_height = game.tetris.field_height() as f32 / 2.0;
+
+        //the next pieces may reach further down than the playfield
+        let next_bottom = half_height - 3.5 - 2.0 * half_next_height;
+        visible_half_height = visible_half_height.max(half_height).max(-next_bottom);
+        visible_half_width = visible_half_width.max(board.translation.x.abs() + half_width + BOARD_SIDE_WIDTH);
     }
+    let distance = CAMERA_BASE_DISTANCE * (visible_half_height * 2.0 / TETRIS_FIELD_DEFAULT_HEIGHT as f32)
+        .max(visible_half_width / CAMERA_BASE_HALF_WIDTH)
+        .max(1.0);
+    camera_transform.translation.z = distance;
 }
 
-/// Activates and deactivates the pause screen upon a press of the Escape key. 
+/// Activates and deactivates the pause screen upon a press of a pause binding of any player. 
 fn manage_pause(
     mut app_state: ResMut<IsAppRunning>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    binding_input: BindingInput,
+    settings: Res<Settings>,
+    player_query: Query<&Board>,
+    waiting_query: Query<(), With<WaitingForNewKeyBind>>,
     show_paused_menu: Res<crate::ui::SpawnPauseSystem>,
     mut commands: Commands,
     previous_state_query: Query<(&GamePausedPreviousState, Entity)>,
     paused_top_div_query: Query<Entity, With<crate::ui::PausedTopDiv>>,
     settings_tap_query: Query<Entity, With<crate::ui::SettingsTab>>,
+    mut paused_writer: EventWriter<Paused>,
+    mut resumed_writer: EventWriter<Resumed>,
+    board_query: Query<(&Game, &GameScore, &DropTimer, Has<Playback>)>,
 ) {
+    //the pause binding cancels assigning a new key bind instead
+    if !waiting_query.is_empty() {
+        return;
+    }
+    let pause_pressed = any_player_just_pressed(TetrisInstruction::Pause, &player_query, &settings, &binding_input);
+
     let state = app_state.0;
     //Game is currently not paused
     if state == AppState::Running {
-        if keyboard_input.just_pressed(KeyCode::Escape) {
+        if pause_pressed {
             app_state.0 = AppState::Paused;
             commands.run_system(show_paused_menu.0);
             commands.spawn(GamePausedPreviousState(state));
+            paused_writer.write(Paused);
+
+            save_game(&board_query);
         }
         return;
     }
-    if state == AppState::GameOver {
+    if state == AppState::GameOver || state == AppState::Resumable {
         return;
     }
 
     //game is currently paused
 
     //end pause
-    if keyboard_input.just_pressed(KeyCode::Escape) {
+    if pause_pressed {
         let Ok((previous_state, entity)) = previous_state_query.single() else {return;};
         app_state.0 = previous_state.0;
         commands.entity(entity).despawn();
+        resumed_writer.write(Resumed);
 
         let Ok(top_div_entity) = paused_top_div_query.single() else {
             error!("Couldn't find and remove the top div of the paused screen!");
@@ -421,175 +577,328 @@ fn manage_pause(
 #[derive(Component)]
 struct GamePausedPreviousState(AppState);
 
-/// Only runs upon updating keybinds or when the game is running. When updating keybinds, assigns the
-/// new key or when pressing Escape cancels the assigning process. Otherwise updates the game by 
-/// translating user input to API calls and automatically dropping down pieces by ticking the here-
-/// managed-timer. 
-fn update_game_state(
-        game_query: Query<&mut Game>, 
-        time: Res<Time>, 
-        mut timer: ResMut<DropTimer>,
-        keyboard_input: Res<ButtonInput<KeyCode>>,
-        mut update_cube_color: ResMut<RecolorCubes>,
-        mut game_score: ResMut<GameScore>,
-        mut running: ResMut<IsAppRunning>,
-        mut settings: ResMut<Settings>,
-        assigning_keybind_query: Query<(Entity, &WaitingForNewKeyBind)>,
+/// Returns true if any player with a board has pressed one of the bindings of the given instruction 
+/// this frame. 
+fn any_player_just_pressed(
+    instruction: TetrisInstruction, 
+    player_query: &Query<&Board>, 
+    settings: &Settings, 
+    binding_input: &BindingInput,
+) -> bool {
+    player_query.iter().any(|board| {
+        settings.player_key_binds(board.player).get(&instruction).just_pressed(binding_input, board.player)
+    })
+}
 
-        mut commands: Commands, 
-        show_game_over: Res<crate::ui::SpawnGameOverSystem>,
-        clear_keybind_clicks: Res<crate::ui::ClearKeybindClicks>,
-    ) {
-    let key_binds = &mut settings.key_binds;
-    if let Ok((entity, waiting)) = assigning_keybind_query.single() {
-        if let Some(key) = keyboard_input.get_just_pressed().next() {
-            if *key != KeyCode::Escape {
-                match waiting.primary {
-                    1 => {
-                        key_binds.get_mut(&waiting.selected_tetris_instruction).primary_key = *key;
-                    }
-                    2 => {
-                        key_binds.get_mut(&waiting.selected_tetris_instruction).secondary_key = Some(*key);
-                    }
-                    x => {
-                        error!("Illegal state, no more than two keys per action allows. Expected value 1 or 2, got: {x}!");
-                        panic!();
-                    }
-                }
-            }
+/// Carries out the meta instructions which are not given to the game itself: holding quick restart 
+/// starts a new game, while the game is running or over, and toggle ghost shows or hides the ghost piece. 
+fn handle_meta_instructions(
+    board_query: Query<(Entity, &Board, &mut RestartHold)>,
+    player_query: Query<&Board>,
+    game_over_screen_query: Query<Entity, With<crate::ui::NewGameTopDiv>>,
+    binding_input: BindingInput,
+    mut settings: ResMut<Settings>,
+    mut running: ResMut<IsAppRunning>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    if running.0 != AppState::Running && running.0 != AppState::GameOver {
+        return;
+    }
 
-            commands.entity(entity).despawn();
-            commands.run_system(clear_keybind_clicks.0);
+    if running.0 == AppState::Running 
+            && any_player_just_pressed(TetrisInstruction::ToggleGhost, &player_query, &settings, &binding_input) {
+        settings.ghost_visible = !settings.ghost_visible;
+        if let Err(err) = settings.write_to_file() {
+            error!("Could not save current settings. Error: {}", err);
+        }
+    }
+
+    //the restart has to be held down to avoid restarting by accident, and pressed again for the next one
+    let mut restart = false;
+    let mut boards = Vec::new();
+    for (entity, board, mut hold) in board_query {
+        let key_bind = settings.player_key_binds(board.player).get(&TetrisInstruction::QuickRestart);
+        if key_bind.just_pressed(&binding_input, board.player) {
+            hold.0 = Some(Duration::ZERO);
+        } else if !key_bind.pressed(&binding_input, board.player) {
+            hold.0 = None;
         }
 
+        if let Some(held) = &mut hold.0 {
+            *held += time.delta();
+            restart |= *held >= QUICK_RESTART_HOLD;
+        }
+        boards.push(entity);
+    }
+    if !restart {
         return;
     }
-    
+
+    for entity in boards.into_iter().chain(game_over_screen_query) {
+        commands.entity(entity).despawn();
+    }
+    spawn_boards(&mut commands, &settings);
+    running.0 = AppState::Running;
+}
+
+/// Only runs while a key bind is being updated. Assigns the next pressed key, gamepad button or stick 
+/// direction, or cancels the assigning process upon a press of a pause binding of the player. 
+fn capture_key_bind(
+    assigning_keybind_query: Query<(Entity, &WaitingForNewKeyBind)>,
+    binding_input: BindingInput,
+    mut settings: ResMut<Settings>,
+    mut commands: Commands, 
+    clear_keybind_clicks: Res<crate::ui::ClearKeybindClicks>,
+) {
+    let Ok((entity, waiting)) = assigning_keybind_query.single() else {return;};
+    let Some(binding) = binding_input.any_just_pressed() else {return;};
+
+    let pause = settings.player_key_binds(waiting.player).get(&TetrisInstruction::Pause);
+    if !pause.bindings.contains(&binding) {
+        settings.bind(waiting.player, waiting.selected_tetris_instruction, waiting.index, binding);
+
+        if let Err(err) = settings.write_to_file() {
+            error!("Could not save current settings. Error: {}", err);
+        }
+    }
+
+    commands.entity(entity).despawn();
+    commands.run_system(clear_keybind_clicks.0);
+}
+
+/// The input source of boards played on this device. Queues a tick for the lock delay, gravity drops 
+/// by ticking the drop timer and the instructions bound to the keys and gamepad buttons of the player. 
+fn queue_local_input(
+    board_query: Query<(&Game, &Board, &mut DropTimer, &mut AutoShift, &GameScore, &mut InputQueue), Without<Playback>>,
+    time: Res<Time>, 
+    binding_input: BindingInput,
+    settings: Res<Settings>,
+    running: Res<IsAppRunning>,
+) {
     if !(running.0 == AppState::Running) {
         return;
     }
 
-    if game_query.is_empty() {
-        error!("Game is missing!");
+    for (game, board, mut timer, mut auto_shift, game_score, mut queue) in board_query {
+        //let the lock delay of a grounded piece run down
+        queue.0.push(ReplayAction::Tick(time.delta()));
+
+        //check if piece drops automatically
+        if timer.0.tick(time.delta()).just_finished() {
+            queue.0.push(ReplayAction::Gravity);
+
+            timer.0.set_duration(Duration::from_secs_f64(level_to_drop_duration(game_score.level)));
+        }
+
+        //left, right and drop are given by the auto shift, which includes their first press
+        let key_binds = settings.player_key_binds(board.player);
+        let pressed = TetrisInstruction::all_instructions_array()
+            .into_iter()
+            .filter(|instruction| !matches!(instruction, TetrisInstruction::Left | TetrisInstruction::Right | TetrisInstruction::Drop))
+            .filter(|instruction| key_binds.get(instruction).just_pressed(&binding_input, board.player));
+
+        //held keys keep moving the piece, soft drops follow the gravity of the current level
+        let soft_drop_interval = Duration::from_secs_f64(level_to_drop_duration(game_score.level))
+            / settings.handling.soft_drop_factor.max(1);
+        let held = auto_shift.held_instructions(
+            key_binds, 
+            &binding_input, 
+            board.player,
+            &settings.handling, 
+            soft_drop_interval, 
+            time.delta(), 
+            game.tetris.field_width(),
+        );
+
+        for instruction in held.into_iter().chain(pressed) {
+            //dropping by hand restarts the gravity
+            if instruction == TetrisInstruction::Drop {
+                timer.0.reset();
+            }
+
+            queue.0.push(ReplayAction::Instruction(instruction));
+        }
+    }
+}
+
+/// The input source of boards playing back a replay. Queues the recorded actions up to the current 
+/// position of the playback, which is controlled with the Space and Arrow keys. Seeking backwards 
+/// restarts the game of the board. 
+fn queue_replay_input(
+    board_query: Query<(&mut Game, &mut GameScore, &mut Playback, &mut InputQueue)>,
+    time: Res<Time>, 
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    running: Res<IsAppRunning>,
+) {
+    if !(running.0 == AppState::Running) {
         return;
     }
-    let mut game = game_query.into_iter().next().unwrap();
-
-    //check if piece drops automatically
-    if timer.0.tick(time.delta()).just_finished() {
-        let result = game.tetris.drop();
-        if let Ok((false, Some(nbr_of_lines))) = result {
-            let add_score = match nbr_of_lines {
-                0 => {0}
-                1 => {ONE_LINE_SCORE}
-                2 => {TWO_LINE_SCORE}
-                3 => {THREE_LINE_SCORE}
-                4 => {FOUR_LINE_SCORE}
-                _ => {
-                    error!("Unexpected number of lines cleared after drop to bottom. Expected range: [0; 4], Actual: {nbr_of_lines}");
-                    panic!();
-                }
-            };
-            game_score.change(add_score, nbr_of_lines);
-        } else if result.is_err() {
-            running.0 = AppState::GameOver;
-            commands.run_system(show_game_over.0);
-        }
-        update_cube_color.0 = true;
-
-        timer.0.set_duration(Duration::from_secs_f64(level_to_drop_duration(game_score.level)));
-    }
-
-    //check for moving left
-    if keyboard_input.just_pressed(key_binds.get(&TetrisInstruction::Left).primary_key) ||
-            key_binds.get(&TetrisInstruction::Left).secondary_key.is_some_and(|k| keyboard_input.just_pressed(k)) {
-        let _ = game.tetris.try_left();
-    }
-
-    //check for moving right
-    if keyboard_input.just_pressed(key_binds.get(&TetrisInstruction::Right).primary_key) ||
-            key_binds.get(&TetrisInstruction::Right).secondary_key.is_some_and(|k| keyboard_input.just_pressed(k)) {
-        let _ = game.tetris.try_right();
-    }
-
-    //drop one level
-    if keyboard_input.just_pressed(key_binds.get(&TetrisInstruction::Drop).primary_key) ||
-            key_binds.get(&TetrisInstruction::Drop).secondary_key.is_some_and(|k| keyboard_input.just_pressed(k)) {
-        let result = game.tetris.drop();
-        if let Ok((false, Some(nbr_of_lines))) = result {
-            let add_score = match nbr_of_lines {
-                0 => {0}
-                1 => {ONE_LINE_SCORE}
-                2 => {TWO_LINE_SCORE}
-                3 => {THREE_LINE_SCORE}
-                4 => {FOUR_LINE_SCORE}
-                _ => {
-                    error!("Unexpected number of lines cleared after drop to bottom. Expected range: [0; 4], Actual: {nbr_of_lines}");
-                    panic!();
-                }
-            };
-            game_score.change(add_score, nbr_of_lines);
-        } else if result.is_err() {
-            running.0 = AppState::GameOver;
-            commands.run_system(show_game_over.0);
+
+    for (mut game, mut game_score, mut playback, mut queue) in board_query {
+        //pause and resume the replay
+        if keyboard_input.just_pressed(KeyCode::Space) {
+            playback.paused = !playback.paused;
         }
-        game_score.change(SLOW_DROP_SCORE, 0);
 
-        timer.0.reset();
-        update_cube_color.0 = true;
-    }
+        //change the replay speed
+        if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+            playback.speed_up();
+        }
+        if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+            playback.slow_down();
+        }
 
-    //drop all the way down 
-    if keyboard_input.just_pressed(key_binds.get(&TetrisInstruction::FullDrop).primary_key) ||
-            key_binds.get(&TetrisInstruction::FullDrop).secondary_key.is_some_and(|k| keyboard_input.just_pressed(k)) {
-        let result = game.tetris.drop_completely_down();
-        if result.is_err() {
-            running.0 = AppState::GameOver;
-            commands.run_system(show_game_over.0);
+        //seek back and forth in the replay
+        let seek_target = if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
+            Some(playback.position().saturating_sub(REPLAY_SEEK_STEP))
+        } else if keyboard_input.just_pressed(KeyCode::ArrowRight) {
+            Some(playback.position() + REPLAY_SEEK_STEP)
         } else {
-            let (nbr_of_dropped_cells, nbr_of_cleared_lines) = result.unwrap();
-            let mut add_score = match nbr_of_cleared_lines {
-                0 => {0}
-                1 => {ONE_LINE_SCORE}
-                2 => {TWO_LINE_SCORE}
-                3 => {THREE_LINE_SCORE}
-                4 => {FOUR_LINE_SCORE}
-                _ => {
-                    error!("Unexpected number of lines cleared after drop to bottom. Expected range: [0; 4], Actual: {nbr_of_cleared_lines}");
-                    panic!();
-                }
-            };
-            add_score += nbr_of_dropped_cells * FAST_DROP_SCORE;
-            game_score.change(add_score, nbr_of_cleared_lines);
+            None
+        };
+        if let Some(target) = seek_target && playback.seek(target) {
+            *game = playback.replay.game();
+            *game_score = GameScore::default();
+            queue.0.clear();
         }
 
-        update_cube_color.0 = true;
+        let actions = playback.advance(time.delta());
+        queue.0.extend(actions);
     }
+}
 
-    //spin active piece counterclockwise
-    if keyboard_input.just_pressed(key_binds.get(&TetrisInstruction::RotateCounter).primary_key) ||
-            key_binds.get(&TetrisInstruction::RotateCounter).secondary_key.is_some_and(|k| keyboard_input.just_pressed(k)) {
-        game.tetris.spin_counter_90();
+/// Only runs when the game is running. Applies the queued actions of every board to its model, 
+/// records them unless the board plays back a replay and reacts to the events of the model. Garbage 
+/// sent by one board is queued on all others. A board stops applying actions once its game is over. 
+fn apply_input_queues(
+        mut board_query: Query<(Entity, &mut Game, &Board, &mut DropTimer, &mut GameScore, &mut InputQueue, Has<Playback>, &mut GarbageRng)>, 
+        mut running: ResMut<IsAppRunning>,
+        mut commands: Commands, 
+        show_game_over: Res<crate::ui::SpawnGameOverSystem>,
+        mut gameplay_events: GameplayEventWriters,
+    ) {
+    if !(running.0 == AppState::Running) {
+        return;
     }
 
-    //spin active piece clockwise
-    if keyboard_input.just_pressed(key_binds.get(&TetrisInstruction::RotateClock).primary_key) ||
-            key_binds.get(&TetrisInstruction::RotateClock).secondary_key.is_some_and(|k| keyboard_input.just_pressed(k)) {
-        game.tetris.spin_clock_90();
+    let players = board_query.iter().count();
+    let mut attacks = Vec::new();
+    let mut losers = Vec::new();
+
+    for (board_entity, mut board_game, board, mut timer, mut game_score, mut queue, is_playback, _) in &mut board_query {
+        //the cells of the board are only redrawn if the model reports a change, so it is marked by hand
+        let game = board_game.bypass_change_detection();
+        let mut model_changed = false;
+
+        //meta instructions are carried out by the scene, so they neither reach the model nor the replay
+        let actions = queue.0.drain(..)
+            .filter(|action| !matches!(action, ReplayAction::Instruction(instruction) if instruction.is_meta()));
+
+        //once the game is over, the rest of the queue is dropped, so the model can not end it again
+        let mut events: Vec<GameEvent> = game.tetris.drain_events().collect();
+        for action in actions {
+            if events.iter().any(|event| matches!(event, GameEvent::GameOver(_))) {
+                break;
+            }
+            action.apply(&mut game.tetris);
+            let first_new_event = events.len();
+            events.extend(game.tetris.drain_events());
+            if !is_playback {
+                let locked = events[first_new_event..].iter().any(|event| matches!(event, GameEvent::Locked(_)));
+                game.replay.record(action, locked);
+            }
+        }
+
+        //react to everything that happened in the model this frame
+        let previous_level = game_score.level;
+        for event in events {
+            model_changed = true;
+            match event {
+                GameEvent::GarbageSent(rows) => {
+                    attacks.push((board_entity, rows));
+                }
+                GameEvent::Held(piece) => {
+                    gameplay_events.hold_used.write(HoldUsed { board: board_entity, piece });
+                }
+                GameEvent::Locked(piece) => {
+                    gameplay_events.piece_locked.write(PieceLocked { board: board_entity, piece });
+                }
+                GameEvent::LinesCleared(rows) => {
+                    gameplay_events.lines_cleared.write(LinesCleared { board: board_entity, count: rows.len() as u32, rows });
+                }
+                GameEvent::GameOver(reason) => {
+                    losers.push(board.player);
+                    gameplay_events.game_over.write(GameOver { board: board_entity, reason });
+
+                    //keep the finished game, unless it is a replay itself or a versus game
+                    if !is_playback && players == 1 {
+                        if let Err(err) = game.replay.write_to_file() {
+                            error!("Could not save the replay. Error: {}", err);
+                        }
+
+                        //a finished game can not be resumed
+                        let _ = std::fs::remove_file(SAVED_GAME_PATH);
+                    }
+                }
+                GameEvent::PieceSpawned(_) | GameEvent::GarbageInserted(_) | GameEvent::Moved(_) | 
+                        GameEvent::Rotated { .. } | GameEvent::HardDropped(_) => {}
+            }
+        }
+
+        //the model keeps the score, only mirror it
+        let score = game.tetris.score();
+        game_score.score = score.points;
+        game_score.level = score.level;
+        if game_score.level > previous_level {
+            timer.0.set_duration(Duration::from_secs_f64(level_to_drop_duration(game_score.level)));
+            gameplay_events.level_up.write(LevelUp { board: board_entity, level: game_score.level });
+        }
+
+        if model_changed {
+            board_game.set_changed();
+        }
     }
 
-    //switch active peace with stored piece
-    if keyboard_input.just_pressed(key_binds.get(&TetrisInstruction::Store).primary_key) ||
-            key_binds.get(&TetrisInstruction::Store).secondary_key.is_some_and(|k| keyboard_input.just_pressed(k)) {
-        let _ = game.tetris.try_switch_active_piece();
-        update_cube_color.0 = true;
+    //every attack sends garbage with a random hole to all other boards
+    if !attacks.is_empty() {
+        for (board_entity, mut game, .., mut garbage_rng) in &mut board_query {
+            for (attacker, rows) in &attacks {
+                if *attacker != board_entity {
+                    let hole_column = garbage_rng.0.gen_range(0..game.tetris.field_width());
+                    game.tetris.add_garbage(*rows, hole_column);
+                }
+            }
+        }
+    }
+
+    //the game ends as soon as one board tops out, the last remaining player wins a versus game
+    if !losers.is_empty() {
+        running.0 = AppState::GameOver;
+
+        let survivors: Vec<usize> = board_query.iter()
+            .map(|(_, _, board, ..)| board.player)
+            .filter(|player| !losers.contains(player))
+            .collect();
+        let winner = if players > 1 && survivors.len() == 1 {Some(survivors[0])} else {None};
+        commands.run_system_with(show_game_over.0, winner);
     }
 }
 
+/// The writers for all gameplay events sent by [apply_input_queues]. 
+#[derive(SystemParam)]
+struct GameplayEventWriters<'w> {
+    lines_cleared: EventWriter<'w, LinesCleared>,
+    piece_locked: EventWriter<'w, PieceLocked>,
+    level_up: EventWriter<'w, LevelUp>,
+    game_over: EventWriter<'w, GameOver>,
+    hold_used: EventWriter<'w, HoldUsed>,
+}
+
 /// Updates audio speed and volume. 
 fn update_audio(
     mut audio_query: Query<&mut AudioSink>,
-    score: Res<GameScore>,
+    score_query: Query<&GameScore>,
     running: Res<IsAppRunning>,
     settings: Res<crate::ui::Settings>,
 ) {
@@ -597,12 +906,14 @@ fn update_audio(
         AppState::Running => {
             let Ok(mut sink) = audio_query.single_mut() else {return};
 
+            //the music follows the board with the highest level
+            let level = score_query.iter().map(|score| score.level).max().unwrap_or(0);
             let mut speed = 1.0;
-            if score.level >= 10 {
+            if level >= 10 {
                 speed = 1.2;
             }
-            if score.level >= 15 {
-                speed = (1.4 + 0.2 * (score.level - 15) as f32).max(2.4);
+            if level >= 15 {
+                speed = (1.4 + 0.2 * (level - 15) as f32).max(2.4);
             } 
 
             sink.set_speed(speed);
@@ -626,8 +937,38 @@ fn level_to_drop_duration(level: u32) -> f64 {
     (BASE_DROP_DURATION_SECS - level * 0.05).max(0.05)
 }
 
+/// Returns the offset which centers the cells of the playfield around the origin. It has to be 
+/// subtracted from a cell position. 
+fn field_offset<T: rand::Rng + Send>(tetris: &engine::model::Tetris<T>) -> Vec3 {
+    Vec3::new(
+        tetris.field_width() as f32 / 2.0 - 0.5, 
+        tetris.field_height() as f32 / 2.0 - 0.5, 
+        0.0,
+    )
+}
+
+/// Returns the offset which places the cells of the first next piece in its frame to the right of the 
+/// playfield. It has to be added to a cell position. 
+fn next_piece_offset<T: rand::Rng + Send>(tetris: &engine::model::Tetris<T>) -> Vec3 {
+    Vec3::new(
+        tetris.field_width() as f32 / 2.0 + 1.5, 
+        tetris.field_height() as f32 / 2.0 - 5.0, 
+        0.0,
+    )
+}
+
+/// Returns the offset which places the cells of the stored piece in its frame to the left of the 
+/// playfield. It has to be added to a cell position. 
+fn stored_piece_offset<T: rand::Rng + Send>(tetris: &engine::model::Tetris<T>) -> Vec3 {
+    Vec3::new(
+        -(tetris.field_width() as f32 / 2.0) - 4.5, 
+        tetris.field_height() as f32 / 2.0 - 5.0, 
+        0.0,
+    )
+}
+
 /// A 2d integer position struct. 
-#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 struct CellPosition {
     x: i32, 
     y: i32,
@@ -668,45 +1009,264 @@ struct MaterialsHandle(HashMap<engine::model::CellStatus, Handle<StandardMateria
 #[derive(Resource)]
 struct LineMaterialHandle(Handle<LineMaterial>);
 
-/// The timer that determines when the active piece next drops. 
-#[derive(Resource)]
+/// The timer that determines when the active piece of a board next drops. 
+#[derive(Clone, Component, Serialize, Deserialize)]
 struct DropTimer(Timer);
 
-/// Flag wether in the next pass all cube colors should be overwritten. 
-#[derive(Resource)]
-struct RecolorCubes(bool);
+impl Default for DropTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(2.0, TimerMode::Repeating))
+    }
+}
 
-/// Marks a cube entity as a cube on the main field.
-#[derive(Component)]
-struct MainPixelMarker;
+/// The actions queued for a board this frame by its input source, in the order they are applied to 
+/// its model by [apply_input_queues]. Every source, be it the players keyboard and gamepad, a replay, a 
+/// remote player or a bot, is a system filling the queues of the boards it controls. 
+#[derive(Component, Default)]
+struct InputQueue(Vec<ReplayAction>);
 
-/// Marks a cube entity as a next piece cube. 
+/// How long the quick restart of the player of a board has been held down for since it was pressed. 
+#[derive(Component, Default)]
+struct RestartHold(Option<Duration>);
+
+/// The random number generator drawing the hole columns of the garbage sent to a board. It is seeded 
+/// from the seed of the game, so the same game gets the same holes. 
 #[derive(Component)]
-struct NextPixelMarker;
+struct GarbageRng(GameRng);
+
+/// The keys of a board which are held down and for how long, see [Handling]. 
+#[derive(Component, Default)]
+struct AutoShift {
+    /// The held horizontal direction and the time it has been held for, minus the repeated moves. 
+    direction: Option<(TetrisInstruction, Duration)>,
+    /// The time drop has been held for, minus the soft drops. 
+    soft_drop: Duration,
+}
+
+impl AutoShift {
+    /// Returns the moves and soft drops given by the left, right and drop keys this frame, including 
+    /// their first press, see [auto_repeat]. A piece moves to the wall in a single frame if the auto 
+    /// repeat rate is zero. 
+    fn held_instructions(
+        &mut self, 
+        key_binds: &KeyBinds, 
+        input: &BindingInput, 
+        player: usize,
+        handling: &Handling, 
+        soft_drop_interval: Duration, 
+        delta: Duration, 
+        field_width: u32,
+    ) -> Vec<TetrisInstruction> {
+        let mut instructions = Vec::new();
+        let directions = [TetrisInstruction::Left, TetrisInstruction::Right];
+
+        //the last pressed direction wins, releasing it falls back to the other one if that is still held
+        let held_direction = match directions.into_iter().find(|d| key_binds.get(d).just_pressed(input, player)) {
+            Some(direction) => Some((direction, None)),
+            None => {
+                if let Some((direction, _)) = self.direction && !key_binds.get(&direction).pressed(input, player) {
+                    self.direction = directions.into_iter()
+                        .find(|d| key_binds.get(d).pressed(input, player))
+                        .map(|d| (d, Duration::ZERO));
+                }
+                self.direction.map(|(direction, held)| (direction, Some(held)))
+            }
+        };
+        if let Some((direction, held)) = held_direction {
+            let das = Duration::from_millis(handling.das_millis as u64);
+            let arr = Duration::from_millis(handling.arr_millis as u64);
+            let (held_after, fired) = auto_repeat(held, delta, das, arr);
+            self.direction = Some((direction, held_after));
+
+            //without an auto repeat rate every repeat moves the piece all the way to the wall
+            let moves = if arr.is_zero() && held.is_some() {fired * field_width} else {fired};
+            instructions.extend(std::iter::repeat_n(direction, moves as usize));
+        }
+
+        let drop = key_binds.get(&TetrisInstruction::Drop);
+        if drop.just_pressed(input, player) || drop.pressed(input, player) {
+            let held = (!drop.just_pressed(input, player)).then_some(self.soft_drop);
+            let (held_after, fired) = auto_repeat(held, delta, soft_drop_interval, soft_drop_interval);
+            self.soft_drop = held_after;
+            instructions.extend(std::iter::repeat_n(TetrisInstruction::Drop, fired as usize));
+        } else {
+            self.soft_drop = Duration::ZERO;
+        }
+
+        instructions
+    }
+}
+
+/// Returns how often a held key fires this frame, along with the time it has been held for afterwards, 
+/// minus the time used up by its repeats. The held time is None on the frame the key is pressed, on 
+/// which it fires once. It fires again once it has been held for the delay and then once every 
+/// interval, or once every frame if the interval is zero. 
+fn auto_repeat(held: Option<Duration>, delta: Duration, delay: Duration, interval: Duration) -> (Duration, u32) {
+    let Some(mut held) = held else {
+        return (Duration::ZERO, 1);
+    };
+
+    held += delta;
+    if held < delay {
+        return (held, 0);
+    }
+    if interval.is_zero() {
+        return (delay, 1);
+    }
+
+    let mut fired = 0;
+    while held >= delay {
+        fired += 1;
+        held -= interval;
+    }
+    (held, fired)
+}
+
+/// Marks a line cube entity as the frame around one of the displayed parts of the game. 
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+enum BoardFrame {
+    Field,
+    Next,
+    Stored,
+}
 
-/// Marks a cube entity as a stored piece cube. 
+/// A grid of cells of the game of a board, displayed by one cube per cell. The grid entity is a child 
+/// of the board, the cubes are children of the grid placed at the position of their cell. 
 #[derive(Component)]
-struct StoredPixelMarker;
+#[require(Transform, Visibility)]
+struct CellGrid {
+    source: CellSource,
+    /// Where the (0, 0) cell is displayed relative to the board. 
+    origin: Vec3,
+    cell_size: f32,
+    style: CellStyle,
+    /// All cubes spawned for the grid so far, the ones not needed at the moment are hidden. 
+    cubes: Vec<Entity>,
+}
+
+impl CellGrid {
+    fn new(source: CellSource, origin: Vec3, style: CellStyle) -> Self {
+        Self {
+            source,
+            origin,
+            cell_size: 1.0,
+            style,
+            cubes: Vec::new(),
+        }
+    }
+}
+
+/// The part of a game a [CellGrid] displays. 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CellSource {
+    /// The locked cells and the active piece. 
+    Field,
+    Ghost,
+    /// The upcoming pieces, stacked top to bottom in the order they will be played. 
+    Next,
+    Stored,
+}
+
+impl CellSource {
+    /// Returns every cell to be displayed along with its color. The ghost piece has no color and 
+    /// its cells are reported as empty, none are reported if it is hidden in the settings. 
+    fn cells<T: rand::Rng + Send>(self, tetris: &engine::model::Tetris<T>, settings: &Settings) -> Vec<(CellStatus, CellPosition)> {
+        match self {
+            Self::Field => {
+                tetris.get_block_list().into_iter()
+                    .chain(tetris.get_active_piece_list())
+                    .map(|(cell, x, y)| (cell, CellPosition::new(x as i32, y as i32)))
+                    .collect()
+            }
+            Self::Ghost if !settings.ghost_visible => Vec::new(),
+            Self::Ghost => {
+                tetris.get_ghost_piece_list().into_iter()
+                    .map(|(x, y)| (CellStatus::Empty, CellPosition::new(x as i32, y as i32)))
+                    .collect()
+            }
+            Self::Next => {
+                tetris.get_next_block_lists(settings.preview_count).into_iter()
+                    .enumerate()
+                    .flat_map(|(index, block_list)| block_list.map(|(cell, x, y)| 
+                        (cell, CellPosition::new(x as i32, y as i32 - index as i32 * PREVIEW_SLOT_HEIGHT))
+                    ))
+                    .collect()
+            }
+            Self::Stored => {
+                //an empty hold has no cells
+                tetris.get_stored_block_list().into_iter()
+                    .flatten()
+                    .map(|(cell, x, y)| (cell, CellPosition::new(x as i32, y as i32)))
+                    .collect()
+            }
+        }
+    }
+}
 
-/// Marks a line cube entity as a ghost piece cube. 
+/// How the cells of a [CellGrid] are drawn. 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CellStyle {
+    /// Cubes colored like the piece occupying the cell. 
+    Solid,
+    /// Small line cubes. 
+    Outline,
+}
+
+/// Marks a cube entity as one of the cubes of a [CellGrid]. 
 #[derive(Component)]
-struct GhostPixelMarker;
+struct GridCube;
 
-/// A struct that holds the users score. 
-#[derive(Debug, Default, Resource)]
+/// A struct that holds the score of a board, mirrored from the score of its [Game]. 
+#[derive(Clone, Component, Debug, Default, Serialize, Deserialize)]
 pub struct GameScore {
     pub score: u32,
     pub level: u32,
-    cleared_lines: u32,
 }
 
-impl GameScore {
-    /// Add specified score and lines to the score without corrupting inner state. 
-    fn change(&mut self, add_score: u32, add_lines: u32) {
-        self.score += add_score;
-        self.cleared_lines += add_lines;
-        self.level = (self.cleared_lines * DIFFICULTY) / 10;
-    }
+/// Sent whenever a locked piece completed one or more lines on the given board. 
+#[derive(Event, Clone, Debug)]
+pub struct LinesCleared {
+    pub board: Entity,
+    pub count: u32,
+    /// The height indices the cleared lines had before clearing, from bottom to top. 
+    pub rows: Vec<u32>,
+}
+
+/// Sent whenever the active piece of the given board has been locked into its field. 
+#[derive(Event, Clone, Copy, Debug)]
+pub struct PieceLocked {
+    pub board: Entity,
+    pub piece: Tetromino,
+}
+
+/// Sent whenever the cleared lines raised the level of the given board. Contains the new level. 
+#[derive(Event, Clone, Copy, Debug)]
+pub struct LevelUp {
+    pub board: Entity,
+    pub level: u32,
+}
+
+/// Sent when the game of the given board has ended along with the reason it ended. 
+#[derive(Event, Clone, Copy, Debug)]
+pub struct GameOver {
+    pub board: Entity,
+    pub reason: engine::model::GameOver,
+}
+
+/// Sent when the game has been paused. 
+#[derive(Event, Clone, Copy, Debug)]
+pub struct Paused;
+
+/// Sent when the game has been resumed after a pause. 
+#[derive(Event, Clone, Copy, Debug)]
+pub struct Resumed;
+
+/// Sent whenever the active piece of the given board has been switched into storage. Contains the 
+/// stored piece. 
+#[derive(Event, Clone, Copy, Debug)]
+pub struct HoldUsed {
+    pub board: Entity,
+    pub piece: Tetromino,
 }
 
 /// The component wrapper for the current state of the tetris game. 
@@ -719,4 +1279,206 @@ pub enum AppState {
     Running, 
     GameOver, 
     Paused,
+    /// The last game has been left unfinished and can be resumed. 
+    Resumable,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::model::scoring::AttackTable;
+
+    use super::*;
+
+    /// The winner passed to the game over screen, None until the game is over. 
+    #[derive(Resource, Default)]
+    struct ShownWinner(Option<Option<usize>>);
+
+    fn record_winner(In(winner): In<Option<usize>>, mut shown_winner: ResMut<ShownWinner>) {
+        shown_winner.0 = Some(winner);
+    }
+
+    /// Creates an app running [apply_input_queues] on two boards dealing only O pieces, where a perfect 
+    /// clear sends three garbage rows. Returns the app and the boards of both players. 
+    fn versus_app() -> (App, [Entity; 2]) {
+        let mut app = App::new();
+        app.add_event::<LinesCleared>();
+        app.add_event::<PieceLocked>();
+        app.add_event::<LevelUp>();
+        app.add_event::<GameOver>();
+        app.add_event::<HoldUsed>();
+        app.init_resource::<ShownWinner>();
+        app.insert_resource(IsAppRunning(AppState::Running));
+        let show_game_over = app.register_system(record_winner);
+        app.insert_resource(crate::ui::SpawnGameOverSystem(show_game_over));
+        app.add_systems(Update, apply_input_queues);
+
+        let rules = TetrisRules {
+            randomizer: RandomizerKind::Fixed,
+            attack: AttackTable {perfect_clear: 3, ..Default::default()},
+            ..Default::default()
+        };
+        let game = Game::new(0, rules, vec![Tetromino::O]);
+        let mut commands = app.world_mut().commands();
+        let boards = [0, 1].map(|player| spawn_board(&mut commands, game.clone(), player, 2));
+        app.world_mut().flush();
+
+        (app, boards)
+    }
+
+    /// Returns the actions placing five O pieces side by side on the empty floor, which clears a double 
+    /// and the entire field. 
+    fn perfect_clear_with_o_pieces() -> Vec<ReplayAction> {
+        let mut actions = Vec::new();
+        for (direction, steps) in [(TetrisInstruction::Left, 4), (TetrisInstruction::Left, 2), (TetrisInstruction::Left, 0), 
+                (TetrisInstruction::Right, 2), (TetrisInstruction::Right, 4)] {
+            actions.extend(std::iter::repeat_n(ReplayAction::Instruction(direction), steps));
+            actions.push(ReplayAction::Instruction(TetrisInstruction::FullDrop));
+        }
+        actions
+    }
+
+    fn queue(app: &mut App, board: Entity, actions: Vec<ReplayAction>) {
+        app.world_mut().get_mut::<InputQueue>(board).unwrap().0.extend(actions);
+    }
+
+    fn garbage_cells(app: &App, board: Entity) -> usize {
+        app.world().get::<Game>(board).unwrap().tetris.get_block_list()
+            .into_iter()
+            .filter(|(cell, ..)| *cell == CellStatus::Garbage)
+            .count()
+    }
+
+    #[test]
+    fn cleared_lines_send_garbage_to_the_opponent() {
+        let (mut app, [first, second]) = versus_app();
+
+        queue(&mut app, first, perfect_clear_with_o_pieces());
+        app.update();
+        //the garbage waits for the next lock of the opponent
+        assert_eq!(garbage_cells(&app, second), 0);
+
+        queue(&mut app, second, vec![ReplayAction::Instruction(TetrisInstruction::FullDrop)]);
+        app.update();
+        assert_eq!(garbage_cells(&app, second), 3 * 9);
+        assert_eq!(garbage_cells(&app, first), 0);
+    }
+
+    #[test]
+    fn cleared_lines_cancel_pending_garbage_first() {
+        let (mut app, [first, second]) = versus_app();
+        let mut actions = perfect_clear_with_o_pieces();
+        let last_piece = actions.split_off(actions.len() - 5);
+
+        //the opponent has four pieces in place when the garbage arrives and clears with the fifth
+        queue(&mut app, first, perfect_clear_with_o_pieces());
+        queue(&mut app, second, actions);
+        app.update();
+        queue(&mut app, second, last_piece);
+        app.update();
+        queue(&mut app, first, vec![ReplayAction::Instruction(TetrisInstruction::FullDrop)]);
+        app.update();
+
+        assert_eq!(garbage_cells(&app, first), 0);
+        assert_eq!(garbage_cells(&app, second), 0);
+    }
+
+    #[test]
+    fn the_remaining_player_wins_the_versus_game() {
+        let (mut app, [_, second]) = versus_app();
+
+        queue(&mut app, second, vec![
+            ReplayAction::Instruction(TetrisInstruction::Forfeit),
+            ReplayAction::Instruction(TetrisInstruction::Forfeit),
+        ]);
+        app.update();
+
+        assert_eq!(app.world().resource::<IsAppRunning>().0, AppState::GameOver);
+        assert_eq!(app.world().resource::<ShownWinner>().0, Some(Some(0)));
+        assert_eq!(app.world().resource::<Events<GameOver>>().len(), 1);
+    }
+
+    /// Applies the actions to the game and records them like a board played on this device does. 
+    fn play(game: &mut Game, actions: impl IntoIterator<Item = ReplayAction>) {
+        for action in actions {
+            action.apply(&mut game.tetris);
+            let locked = game.tetris.drain_events().any(|event| matches!(event, GameEvent::Locked(_)));
+            game.replay.record(action, locked);
+        }
+    }
+
+    #[test]
+    fn a_saved_game_resumes_where_it_was_left() {
+        let mut game = Game::new(5, TetrisRules::default(), Vec::new());
+        play(&mut game, [
+            TetrisInstruction::Left, 
+            TetrisInstruction::FullDrop, 
+            TetrisInstruction::Store, 
+            TetrisInstruction::RotateClock, 
+            TetrisInstruction::Right, 
+            TetrisInstruction::FullDrop,
+        ].map(ReplayAction::Instruction));
+        //idle frames while the player thinks only lengthen the last tick of the replay
+        play(&mut game, std::iter::repeat_n(ReplayAction::Tick(Duration::from_millis(16)), 1000));
+        let saved_game = SavedGame {
+            game: game.clone(),
+            score: GameScore {score: 120, level: 2},
+            drop_timer: DropTimer::default(),
+        };
+
+        let bytes = serde_cbor::to_vec(&saved_game).unwrap();
+        let mut loaded: SavedGame = serde_cbor::from_slice(&bytes).unwrap();
+
+        assert_eq!(loaded.game.seed, game.seed);
+        assert_eq!((loaded.score.score, loaded.score.level), (120, 2));
+        assert_eq!(loaded.game.replay.actions, game.replay.actions);
+        assert_eq!(loaded.game.replay.actions.len(), 7);
+        //the resumed game deals and plays on exactly like the one that was saved
+        let rest = [TetrisInstruction::Store, TetrisInstruction::FullDrop, TetrisInstruction::FullDrop].map(ReplayAction::Instruction);
+        play(&mut game, rest);
+        play(&mut loaded.game, rest);
+        assert_eq!(loaded.game.tetris.get_block_list(), game.tetris.get_block_list());
+        assert_eq!(loaded.game.tetris.get_active_piece_list(), game.tetris.get_active_piece_list());
+        assert_eq!(loaded.game.tetris.get_stored_block_list(), game.tetris.get_stored_block_list());
+        assert_eq!(loaded.game.tetris.get_next_block_lists(5), game.tetris.get_next_block_lists(5));
+        assert_eq!(loaded.game.replay.actions, game.replay.actions);
+    }
+
+    const DAS: Duration = Duration::from_millis(170);
+    const ARR: Duration = Duration::from_millis(50);
+
+    #[test]
+    fn auto_repeat_fires_on_the_initial_press() {
+        assert_eq!(auto_repeat(None, Duration::from_millis(16), DAS, ARR), (Duration::ZERO, 1));
+    }
+
+    #[test]
+    fn auto_repeat_fires_again_once_the_delay_has_passed() {
+        assert_eq!(auto_repeat(Some(Duration::from_millis(150)), Duration::from_millis(16), DAS, ARR), (Duration::from_millis(166), 0));
+        assert_eq!(auto_repeat(Some(Duration::from_millis(166)), Duration::from_millis(16), DAS, ARR), (Duration::from_millis(132), 1));
+    }
+
+    #[test]
+    fn auto_repeat_fires_every_interval_after_the_delay() {
+        let mut held = None;
+        let mut fired = 0;
+        for _ in 0..30 {
+            let (held_after, fired_now) = auto_repeat(held, Duration::from_millis(10), DAS, ARR);
+            held = Some(held_after);
+            fired += fired_now;
+        }
+
+        //the press, then after 170, 220 and 270 ms of the 290 ms held since
+        assert_eq!(fired, 4);
+        //a long frame catches up on every interval that passed
+        assert_eq!(auto_repeat(Some(Duration::ZERO), Duration::from_millis(300), DAS, ARR), (Duration::from_millis(150), 3));
+    }
+
+    #[test]
+    fn auto_repeat_without_interval_fires_once_every_frame() {
+        let (held, fired) = auto_repeat(Some(Duration::from_millis(160)), Duration::from_millis(16), DAS, Duration::ZERO);
+        assert_eq!((held, fired), (DAS, 1));
+
+        assert_eq!(auto_repeat(Some(held), Duration::from_millis(16), DAS, Duration::ZERO), (DAS, 1));
+        assert_eq!(auto_repeat(Some(held), Duration::from_millis(500), DAS, Duration::ZERO), (DAS, 1));
+    }
 }
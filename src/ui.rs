@@ -1,5 +1,8 @@
+use std::collections::HashSet;
+
 use bevy::prelude::*;
-use bevy::ecs::system::SystemId;
+use bevy::ecs::system::{SystemId, SystemParam};
+use bevy::input::InputSystem;
 use serde::{ser::SerializeStruct, Deserialize, Serialize};
 
 use crate::engine;
@@ -7,24 +10,81 @@ use crate::engine;
 const EMPTY_BACKGROUND_COLOR: bevy::ui::BackgroundColor = BackgroundColor(Color::LinearRgba(LinearRgba { red: 0.0, green: 0.0, blue: 0.0, alpha: 0.0 }));
 const HOVERED_BUTTON_BACKGROUND_COLOR: bevy::ui::BackgroundColor = BackgroundColor(Color::srgb(0.3, 0.3, 0.3));
 const PLAYFIELD_WIDTH_IN_PX: f32 = 400.0;
+const FIELD_MAX_WIDTH: u32 = 40;
+const FIELD_MAX_HEIGHT: u32 = 60;
+const LOCK_DELAY_MAX_MILLIS: u32 = 2000;
+const LOCK_DELAY_STEP_MILLIS: u32 = 50;
+const ATTACK_MAX_ROWS: u32 = 10;
+const PREVIEW_COUNT_DEFAULT: u32 = 5;
+const REPLAY_LIST_MAX_COUNT: usize = 8;
+const DAS_MAX_MILLIS: u32 = 500;
+const DAS_STEP_MILLIS: u32 = 10;
+const ARR_MAX_MILLIS: u32 = 200;
+const ARR_STEP_MILLIS: u32 = 5;
+const SOFT_DROP_FACTOR_MAX: u32 = 40;
+const STICK_THRESHOLD: f32 = 0.5;
 
 pub struct MyUiPlugin;
 
 impl Plugin for MyUiPlugin {
     fn build(&self, app: &mut App) {
+        app.insert_resource(Settings::from_serialized_or_default());
+        app.init_resource::<GamepadBindingState>();
         app.add_plugins(engine::scene::ScenePlugin);
         app.add_systems(Startup, setup);
+        app.add_systems(PreUpdate, update_gamepad_binding_state.after(InputSystem));
         app.add_systems(Update, update_horizontal_pad_window_width);
         app.add_systems(Update, update_score);
         app.add_systems(Update, update_level);
+        app.add_systems(Update, update_game_seed);
+        app.add_systems(Update, log_gameplay_events);
         app.add_systems(Update, new_game_button_listener);
+        app.add_systems(Update, display_replay_list);
+        app.add_systems(Update, watch_replay_button_listener);
+        app.add_systems(Update, resume_game_button_listener);
+        app.add_systems(Update, update_replay_text);
         app.add_systems(Update, audio_button_listener);
         app.add_systems(Update, key_mapping_button_listener);
+        app.add_systems(Update, gameplay_button_listener);
+        app.add_systems(Update, handling_button_listener);
         app.add_systems(Update, display_music_volume_settings);
         app.add_systems(Update, update_music_volume_settings);
-        app.add_systems(Update, update_primary_key_bind_settings);
-        app.add_systems(Update, update_secondary_key_bind_settings);
+        app.add_systems(Update, display_key_bind_lists);
         app.add_systems(Update, individual_keybind_button_listener);
+        app.add_systems(Update, remove_keybind_button_listener);
+        app.add_systems(Update, key_bind_preset_button_listener);
+        app.add_systems(Update, reset_key_binds_button_listener);
+        app.add_systems(Update, display_rotation_system_settings);
+        app.add_systems(Update, update_rotation_system_settings);
+        app.add_systems(Update, display_randomizer_settings);
+        app.add_systems(Update, update_randomizer_settings);
+        app.add_systems(Update, display_scoring_rules_settings);
+        app.add_systems(Update, update_scoring_rules_settings);
+        app.add_systems(Update, display_preview_count_settings);
+        app.add_systems(Update, update_preview_count_settings);
+        app.add_systems(Update, display_lock_delay_settings);
+        app.add_systems(Update, update_lock_delay_settings);
+        app.add_systems(Update, display_lock_reset_settings);
+        app.add_systems(Update, update_lock_reset_settings);
+        app.add_systems(Update, display_hold_rule_settings);
+        app.add_systems(Update, update_hold_rule_settings);
+        app.add_systems(Update, display_cheese_rows_settings);
+        app.add_systems(Update, update_cheese_rows_settings);
+        app.add_systems(Update, display_game_mode_settings);
+        app.add_systems(Update, update_game_mode_settings);
+        app.add_systems(Update, display_attack_settings);
+        app.add_systems(Update, update_attack_settings);
+        app.add_systems(Update, display_das_settings);
+        app.add_systems(Update, update_das_settings);
+        app.add_systems(Update, display_arr_settings);
+        app.add_systems(Update, update_arr_settings);
+        app.add_systems(Update, display_soft_drop_factor_settings);
+        app.add_systems(Update, update_soft_drop_factor_settings);
+        app.add_systems(Update, display_seed_settings);
+        app.add_systems(Update, seed_button_listener);
+        app.add_systems(Update, update_seed_settings);
+        app.add_systems(Update, display_field_size_settings);
+        app.add_systems(Update, update_field_size_settings);
     }
 }
 
@@ -47,15 +107,14 @@ fn setup(
 
     let highlight_clicked_keybinds = commands.register_system(highlight_clicked_keybind);
     commands.insert_resource(HighlghtClickedKeybind(highlight_clicked_keybinds));
-
-    commands.insert_resource(Settings::from_serialized_or_default());
 }
 
-/// One shot function which spawns the game over screen. 
+/// One shot function which spawns the game over screen. Takes the winning player of a versus game. 
 fn spawn_game_over_screen(
+    In(winner): In<Option<usize>>,
     mut commands: Commands,
 ) {
-    commands.spawn(generate_game_over_screen());
+    commands.spawn(generate_game_over_screen(winner));
 }
 
 /// One shot function which spawns the pause game screen. 
@@ -67,7 +126,7 @@ fn spawn_pause_screen(
 
 /// A resource which holds the id of the spawn_game_over_screen function. 
 #[derive(Resource)]
-pub(crate) struct SpawnGameOverSystem(pub SystemId);
+pub(crate) struct SpawnGameOverSystem(pub SystemId<In<Option<usize>>>);
 
 /// A resource which holds the id of the spawn_pause_screen function. 
 #[derive(Resource)]
@@ -83,7 +142,7 @@ pub struct HighlghtClickedKeybind(pub SystemId);
 
 /// One shot function which clears all backgrounds of the individual key bind settings fields. 
 fn clear_keybind_clicks(
-    backgrounds_query: Query<&mut BackgroundColor, (With<TetrisInstruction>, With<Button>)>,
+    backgrounds_query: Query<&mut BackgroundColor, (With<KeyBindClickArea>, With<Button>)>,
 ) {
     for mut background in backgrounds_query {
         *background = EMPTY_BACKGROUND_COLOR;
@@ -92,7 +151,7 @@ fn clear_keybind_clicks(
 
 /// One shot function which changes the background of the clicked key bind settings field. 
 fn highlight_clicked_keybind(
-    backgrounds_query: Query<(&mut BackgroundColor, &TetrisInstruction, &KeyBindClickArea), With<Button>>,
+    backgrounds_query: Query<(&mut BackgroundColor, &TetrisInstruction, &KeyBindClickArea, &KeyBindPlayer), With<Button>>,
     waiting_query: Query<&WaitingForNewKeyBind>,
 ) {
     let Ok(waiting) = waiting_query.single() else {
@@ -100,8 +159,8 @@ fn highlight_clicked_keybind(
         return;
     };
 
-    for (mut background, instruction, number) in backgrounds_query {
-        if *instruction == waiting.selected_tetris_instruction && number.0 == waiting.primary {
+    for (mut background, instruction, number, player) in backgrounds_query {
+        if *instruction == waiting.selected_tetris_instruction && number.0 == waiting.index && player.0 == waiting.player {
             *background = BackgroundColor(Color::srgb(0.5, 0.5, 0.5));
         }
     }
@@ -200,6 +259,8 @@ fn main_content_window() -> impl Bundle + use<> {
         children![
             generate_text_window("Score: ", ScoreTextMarker),
             generate_text_window("Level: ", LevelTextMarker),
+            generate_text_window("Seed: ", GameSeedTextMarker),
+            generate_text_window("", ReplayTextMarker),
         ],
     )
 }
@@ -245,11 +306,12 @@ struct ScoreTextMarker;
 /// Continuously updates the users score with the score from the [engine::scene::GameScore] 
 /// entity. 
 fn update_score(
-    score: Res<engine::scene::GameScore>,
+    board_query: Query<(&engine::scene::Board, &engine::scene::GameScore)>,
     text_query: Query<&mut Text, With<ScoreTextMarker>>,
 ) {
+    let scores = join_board_values(board_query, |score| score.score);
     for mut text in text_query {
-        *text = Text::new(format!("Score: {}", score.score));
+        *text = Text::new(format!("Score: {}", scores));
     }
 }
 
@@ -260,16 +322,118 @@ struct LevelTextMarker;
 /// Continuously updates the users level with the level from the [engine::scene::GameScore] 
 /// entity. 
 fn update_level(
-    score: Res<engine::scene::GameScore>,
+    board_query: Query<(&engine::scene::Board, &engine::scene::GameScore)>,
     text_query: Query<&mut Text, With<LevelTextMarker>>,
 ) {
+    let levels = join_board_values(board_query, |score| score.level);
+    for mut text in text_query {
+        *text = Text::new(format!("Level: {}", levels));
+    }
+}
+
+/// Lists the given value of the scores of all boards, ordered by player. 
+fn join_board_values(
+    board_query: Query<(&engine::scene::Board, &engine::scene::GameScore)>,
+    value: impl Fn(&engine::scene::GameScore) -> u32,
+) -> String {
+    let mut boards: Vec<_> = board_query.iter().collect();
+    boards.sort_by_key(|(board, _)| board.player);
+
+    boards.into_iter()
+        .map(|(_, score)| value(score).to_string())
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// The marker to change the seed of the current game. 
+#[derive(Component)]
+struct GameSeedTextMarker;
+
+/// Continuously updates the seed text with the seed of the [engine::scene::Game]. 
+fn update_game_seed(
+    game_query: Query<&engine::scene::Game>,
+    text_query: Query<&mut Text, With<GameSeedTextMarker>>,
+) {
+    //all boards of a game share the seed
+    let Some(game) = game_query.iter().next() else {return;};
+
+    for mut text in text_query {
+        *text = Text::new(format!("Seed: {}", game.seed));
+    }
+}
+
+/// The marker to change the text describing the replay being played back. 
+#[derive(Component)]
+struct ReplayTextMarker;
+
+/// Continuously updates the replay text with the position and speed of the replay being played back. 
+/// The text is empty while no replay is played back. 
+fn update_replay_text(
+    playback_query: Query<&engine::replay::Playback>,
+    text_query: Query<&mut Text, With<ReplayTextMarker>>,
+) {
+    let replay_text = match playback_query.iter().next() {
+        Some(playback) => format!(
+            "Replay: {} / {} x{}{}", 
+            format_replay_time(playback.position()), 
+            format_replay_time(playback.length()), 
+            playback.speed, 
+            if playback.paused {" (paused)"} else {""},
+        ),
+        None => String::new(),
+    };
+
     for mut text in text_query {
-        *text = Text::new(format!("Level: {}", score.level));
+        *text = Text::new(replay_text.clone());
+    }
+}
+
+/// Formats a time of a replay as minutes and seconds. 
+fn format_replay_time(time: std::time::Duration) -> String {
+    let seconds = time.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Writes the gameplay events sent by the [engine::scene::ScenePlugin] to the log. 
+fn log_gameplay_events(
+    mut lines_cleared_reader: EventReader<engine::scene::LinesCleared>,
+    mut piece_locked_reader: EventReader<engine::scene::PieceLocked>,
+    mut level_up_reader: EventReader<engine::scene::LevelUp>,
+    mut game_over_reader: EventReader<engine::scene::GameOver>,
+    mut paused_reader: EventReader<engine::scene::Paused>,
+    mut resumed_reader: EventReader<engine::scene::Resumed>,
+    mut hold_used_reader: EventReader<engine::scene::HoldUsed>,
+) {
+    for event in piece_locked_reader.read() {
+        debug!("{:?} locked {:?}", event.board, event.piece);
+    }
+    for event in hold_used_reader.read() {
+        debug!("{:?} stored {:?}", event.board, event.piece);
+    }
+    for event in lines_cleared_reader.read() {
+        debug!("{:?} cleared {} lines at rows {:?}", event.board, event.count, event.rows);
+    }
+    for event in level_up_reader.read() {
+        info!("{:?} reached level {}", event.board, event.level);
+    }
+    for event in game_over_reader.read() {
+        info!("Game over for {:?}: {}", event.board, event.reason);
+    }
+    for _ in paused_reader.read() {
+        debug!("Game paused");
+    }
+    for _ in resumed_reader.read() {
+        debug!("Game resumed");
     }
 }
 
-/// Creates the entire screen spanning game over screen UI component. 
-fn generate_game_over_screen() -> impl Bundle + use<> {
+/// Creates the entire screen spanning game over screen UI component. Names the winner of a versus game. 
+fn generate_game_over_screen(winner: Option<usize>) -> impl Bundle + use<> {
+    let title = match winner {
+        Some(player) => format!("PLAYER {} WINS!", player + 1),
+        None => String::from("GAME OVER!"),
+    };
+
     (
         Node {
             width: Val::Percent(100.0),
@@ -290,7 +454,7 @@ fn generate_game_over_screen() -> impl Bundle + use<> {
                 children![
                     (
                         Node::DEFAULT,
-                        Text::new("GAME OVER!"),
+                        Text::new(title),
                         TextFont {
                             font_size: 100.0,
                             ..Default::default()
@@ -312,6 +476,105 @@ fn generate_game_over_screen() -> impl Bundle + use<> {
                         Button,
                         NewGameButton,
                         EMPTY_BACKGROUND_COLOR,
+                    ),
+                ]
+            ),
+            (   //Replays, filled by display_replay_list
+                Node {
+                    flex_direction: FlexDirection::Column, 
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                ReplayList,
+                children![
+                    (
+                        Node::DEFAULT,
+                        Text::new("Watch Replay"),
+                        TextFont {
+                            font_size: 40.0,
+                            ..Default::default()
+                        },
+                    )
+                ],
+            ),
+        ],
+    )
+}
+
+/// Fills every new replay list with a button for each of the most recently saved replays. 
+fn display_replay_list(
+    list_query: Query<Entity, Added<ReplayList>>,
+    mut commands: Commands,
+) {
+    for entity in list_query {
+        for path in engine::replay::Replay::list_files().into_iter().take(REPLAY_LIST_MAX_COUNT) {
+            commands.entity(entity).with_child(generate_watch_replay_button(path));
+        }
+    }
+}
+
+/// Creates the UI component of the button watching the replay saved in the given file. The button is 
+/// labeled with the file name, which names the time the game was started and its seed. 
+fn generate_watch_replay_button(path: std::path::PathBuf) -> impl Bundle + use<> {
+    let label = path.file_stem().map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+
+    (
+        Node::DEFAULT,
+        Text::new(format!(" {label} ")),
+        TextFont {
+            font_size: 25.0,
+            ..Default::default()
+        },
+        Button,
+        WatchReplayButton(path),
+        EMPTY_BACKGROUND_COLOR,
+    )
+}
+
+/// Creates the entire screen spanning screen UI component which offers to resume the last game. It 
+/// shares the top div with the game over screen, so its new game button works the same. 
+pub(crate) fn generate_resume_screen() -> impl Bundle + use<> {
+    (
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column, 
+
+            ..Default::default()
+        },
+        BackgroundColor(Color::srgba(0.5, 0.5, 0.5, 0.5)),
+        NewGameTopDiv,
+        children![
+            (   //Buttons
+                Node {
+                    flex_direction: FlexDirection::Column, 
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                children![
+                    (
+                        Node::DEFAULT,
+                        Text::new(" Resume Last Game "),
+                        TextFont {
+                            font_size: 50.0,
+                            ..Default::default()
+                        },
+                        Button,
+                        ResumeGameButton,
+                        EMPTY_BACKGROUND_COLOR,
+                    ),
+                    (
+                        Node::DEFAULT,
+                        Text::new(" New Game "),
+                        TextFont {
+                            font_size: 50.0,
+                            ..Default::default()
+                        },
+                        Button,
+                        NewGameButton,
+                        EMPTY_BACKGROUND_COLOR,
                     )
                 ]
             )
@@ -319,13 +582,25 @@ fn generate_game_over_screen() -> impl Bundle + use<> {
     )
 }
 
+/// The marker to mark the resume last game button. 
+#[derive(Component)]
+struct ResumeGameButton;
+
 /// The marker to mark the new game button. 
 #[derive(Component)]
 struct NewGameButton;
 
+/// The marker to mark a watch replay button along with the file of the replay it plays back. 
+#[derive(Component)]
+struct WatchReplayButton(std::path::PathBuf);
+
+/// The marker for the list of watch replay buttons on the game over screen. 
+#[derive(Component)]
+struct ReplayList;
+
 /// The marker for the screen spanning div of the game over screen. 
 #[derive(Component)]
-struct NewGameTopDiv;
+pub(crate) struct NewGameTopDiv;
 
 /// Creates the pause menu screen UI component. 
 fn generate_pause_screen() -> impl Bundle + use<> {
@@ -358,6 +633,8 @@ fn generate_pause_screen() -> impl Bundle + use<> {
                 children![
                     generate_top_level_settings_line_element("Audio", AudioButton),
                     generate_top_level_settings_line_element("Key Mapping", KeyMappingButton), 
+                    generate_top_level_settings_line_element("Gameplay", GameplayButton), 
+                    generate_top_level_settings_line_element("Handling", HandlingButton), 
                 ],
             ),
         ],
@@ -448,8 +725,170 @@ fn generate_audio_settings() -> impl Bundle + use<> {
     )   //TODO
 }
 
-/// Creates the UI components which will form the settings for a single action keybind. 
-fn generate_single_key_bind_entry(action_description: &str, instruction: TetrisInstruction) -> impl Bundle + use<> {
+/// Creates the UI components which will form the gameplay settings. Changes to these only apply 
+/// to the next game. 
+fn generate_gameplay_settings() -> impl Bundle + use<> {
+    (
+        Node {
+            flex_direction: FlexDirection::Column,
+            align_content: AlignContent::FlexStart,
+            ..Default::default()
+        },
+        PauseMenuRemovableChildren,
+        children![
+            generate_number_settings_entry("Next Pieces Shown: ", PreviewCountMarker),
+            generate_button_settings_entry("Rotation System (next game): ", RotationSystemButton, RotationSystemTextMarker),
+            generate_button_settings_entry("Randomizer (next game): ", RandomizerButton, RandomizerTextMarker),
+            generate_button_settings_entry("Scoring (next game): ", ScoringRulesButton, ScoringRulesTextMarker),
+            generate_number_settings_entry("Lock Delay in ms (next game): ", LockDelayMarker),
+            generate_button_settings_entry("Lock Delay Reset (next game): ", LockResetButton, LockResetTextMarker),
+            generate_button_settings_entry("Hold (next game): ", HoldRuleButton, HoldRuleTextMarker),
+            generate_button_settings_entry("Seed (next game): ", SeedButton, SeedTextMarker),
+            generate_number_settings_entry("Field Width (next game): ", FieldDimension::Width),
+            generate_number_settings_entry("Field Height (next game): ", FieldDimension::Height),
+            generate_number_settings_entry("Cheese Rows (next game): ", CheeseRowsMarker),
+            generate_versus_settings(),
+        ],
+    )
+}
+
+/// Creates the UI components of the gameplay settings choosing the game mode and the garbage sent in 
+/// versus games. 
+fn generate_versus_settings() -> impl Bundle + use<> {
+    (
+        Node {
+            flex_direction: FlexDirection::Column,
+            align_content: AlignContent::FlexStart,
+            ..Default::default()
+        },
+        children![
+            generate_button_settings_entry("Mode (next game): ", GameModeButton, GameModeTextMarker),
+            generate_number_settings_entry("Garbage for a Single (next game): ", AttackMarker::Lines(0)),
+            generate_number_settings_entry("Garbage for a Double (next game): ", AttackMarker::Lines(1)),
+            generate_number_settings_entry("Garbage for a Triple (next game): ", AttackMarker::Lines(2)),
+            generate_number_settings_entry("Garbage for a Tetris (next game): ", AttackMarker::Lines(3)),
+            generate_number_settings_entry("Garbage for a T-Spin Single (next game): ", AttackMarker::TSpinLines(0)),
+            generate_number_settings_entry("Garbage for a T-Spin Double (next game): ", AttackMarker::TSpinLines(1)),
+            generate_number_settings_entry("Garbage for a T-Spin Triple (next game): ", AttackMarker::TSpinLines(2)),
+            generate_number_settings_entry("Garbage for a Perfect Clear (next game): ", AttackMarker::PerfectClear),
+        ],
+    )
+}
+
+/// Creates the UI components which will form the handling settings, which decide how held keys move 
+/// the active piece. Changes to these apply immediately. 
+fn generate_handling_settings() -> impl Bundle + use<> {
+    (
+        Node {
+            flex_direction: FlexDirection::Column,
+            align_content: AlignContent::FlexStart,
+            ..Default::default()
+        },
+        PauseMenuRemovableChildren,
+        children![
+            generate_number_settings_entry("Delayed Auto Shift in ms: ", DasMarker),
+            generate_number_settings_entry("Auto Repeat Rate in ms (0 is instant): ", ArrMarker),
+            generate_number_settings_entry("Soft Drop Factor: ", SoftDropFactorMarker),
+        ],
+    )
+}
+
+/// Creates the UI components of a single settings entry whose value is changed by clicking it, 
+/// consisting of a description and a button showing the value. The button marker is put on the button, 
+/// the text marker on the value text. 
+fn generate_button_settings_entry<B, T>(description: &str, button_marker: B, text_marker: T) -> impl Bundle + use<B, T> 
+        where B: Component, T: Component {
+    (
+        Node {
+            flex_direction: FlexDirection::Row,
+            margin: UiRect::all(Val::Px(5.0)),
+            padding: UiRect::all(Val::Px(5.0)),
+            ..Default::default()
+        },
+        BackgroundColor(Color::srgb(0.3, 0.3, 0.3)),
+        children![
+            (
+                Node {
+                    align_self: AlignSelf::Center,
+                    ..Default::default()
+                },
+                Text::new(description),
+            ),
+            (
+                Node {
+                    border: UiRect::all(Val::Px(2.0)),
+                    padding: UiRect::all(Val::Px(4.0)),
+                    ..Default::default()
+                },
+                BorderColor(Color::Srgba(Srgba::BLACK)),
+                Button,
+                button_marker,
+                BackgroundColor(Color::srgb(0.5, 0.5, 0.5)),
+                children![
+                    (
+                        Text::new("???"),
+                        text_marker,
+                    ),
+                ],
+            ),
+        ],
+    )
+}
+
+/// Creates the UI components of a single numeric settings entry, consisting of a description, a 
+/// decrease button, the value and an increase button. The given marker is put on the buttons and the 
+/// value text, which are differentiated by [SettingsChange]. 
+fn generate_number_settings_entry<T>(description: &str, marker: T) -> impl Bundle + use<T> 
+where T: Component + Copy {
+    (
+        Node {
+            flex_direction: FlexDirection::Row,
+            margin: UiRect::all(Val::Px(5.0)),
+            padding: UiRect::all(Val::Px(5.0)),
+            ..Default::default()
+        },
+        BackgroundColor(Color::srgb(0.3, 0.3, 0.3)),
+        children![
+            (
+                Node {
+                    align_self: AlignSelf::Center,
+                    ..Default::default()
+                },
+                Text::new(description),
+            ),
+            (
+                Node::DEFAULT,
+                Button,
+                marker,
+                SettingsChange::Decrease,
+                BackgroundColor(Color::srgb(0.5, 0.5, 0.5)),
+                children![
+                    Text::new(" - "),
+                ],
+            ),
+            (
+                Node::DEFAULT,
+                Text::new(" ?? "),
+                marker,
+            ),
+            (
+                Node::DEFAULT,
+                Button,
+                marker,
+                SettingsChange::Increase,
+                BackgroundColor(Color::srgb(0.5, 0.5, 0.5)),
+                children![
+                    Text::new(" + "),
+                ],
+            ),
+        ],
+    )
+}
+
+/// Creates the UI components which will form the settings for a single action keybind of the given player. 
+/// The buttons of the individual bindings are filled in by display_key_bind_lists. 
+fn generate_single_key_bind_entry(action_description: &str, instruction: TetrisInstruction, player: usize) -> impl Bundle + use<> {
+    let player = KeyBindPlayer(player);
     (
         Node {
             flex_direction: FlexDirection::Row,
@@ -474,9 +913,32 @@ fn generate_single_key_bind_entry(action_description: &str, instruction: TetrisI
                     ),
                 ],
             ),
-            (   //primary key bind
+            (   //key binds
+                Node {
+                    flex_direction: FlexDirection::Row,
+                    margin: UiRect::left(Val::Px(10.0)),
+                    ..Default::default()
+                },
+                KeyBindList,
+                instruction,
+                player,
+            ),
+        ],
+    )
+}
+
+/// Creates the UI components of a single bound input, a button showing the binding which can be clicked 
+/// to replace it and a button removing it. 
+fn generate_key_bind_button(binding: InputBinding, index: usize, instruction: TetrisInstruction, player: KeyBindPlayer) -> impl Bundle + use<> {
+    (
+        Node {
+            flex_direction: FlexDirection::Row,
+            margin: UiRect::right(Val::Px(10.0)),
+            ..Default::default()
+        },
+        children![
+            (
                 Node {
-                    margin: UiRect::horizontal(Val::Px(10.0)),  //middle div is responsible for spacing
                     border: UiRect::all(Val::Px(2.0)),
                     padding: UiRect::all(Val::Px(4.0)),
                     ..Default::default()
@@ -484,71 +946,215 @@ fn generate_single_key_bind_entry(action_description: &str, instruction: TetrisI
                 BorderColor(Color::Srgba(Srgba::BLACK)),
                 Button,
                 EMPTY_BACKGROUND_COLOR,
-                KeyBindClickArea(1),
+                KeyBindClickArea(index),
                 instruction,
+                player,
                 children![
-                    (
-                        instruction,
-                        FirstKeyBindTextMarker,
-                        Text::new("PRIMARY KEY BIND"),
-                    )
+                    Text::new(binding_to_str(binding)),
                 ],
             ),
-            (   //secondary key bind
+            (
                 Node {
-                    border: UiRect::all(Val::Px(2.0)),
                     padding: UiRect::all(Val::Px(4.0)),
                     ..Default::default()
                 },
-                BorderColor(Color::Srgba(Srgba::BLACK)),
                 Button,
-                EMPTY_BACKGROUND_COLOR,
-                KeyBindClickArea(2),
+                BackgroundColor(Color::srgb(0.5, 0.5, 0.5)),
+                RemoveKeyBindButton(index),
                 instruction,
+                player,
                 children![
-                    (
-                        instruction,
-                        SecondKeyBindTextMarker,
-                        Text::new("SECONDARY KEY BIND"),
-                    )
+                    Text::new(" x "),
                 ],
             ),
         ],
     )
 }
 
-/// Creates the UI component of the entire key bind settings. 
-fn generate_key_bind_menu() -> impl Bundle + use<> {
+/// Creates the UI component of the button adding a new binding behind the existing ones. 
+fn generate_add_key_bind_button(index: usize, instruction: TetrisInstruction, player: KeyBindPlayer) -> impl Bundle + use<> {
     (
         Node {
-            flex_direction: FlexDirection::Column,
-            align_content: AlignContent::FlexStart,
+            border: UiRect::all(Val::Px(2.0)),
+            padding: UiRect::all(Val::Px(4.0)),
             ..Default::default()
         },
-        PauseMenuRemovableChildren,
+        BorderColor(Color::Srgba(Srgba::BLACK)),
+        Button,
+        EMPTY_BACKGROUND_COLOR,
+        KeyBindClickArea(index),
+        instruction,
+        player,
         children![
-            generate_single_key_bind_entry("Drop", TetrisInstruction::Drop),
-            generate_single_key_bind_entry("Full Drop", TetrisInstruction::FullDrop),
-            generate_single_key_bind_entry("Left", TetrisInstruction::Left),
-            generate_single_key_bind_entry("Right", TetrisInstruction::Right),
-            generate_single_key_bind_entry("Rotate Counter Clock Wise", TetrisInstruction::RotateCounter),
-            generate_single_key_bind_entry("Rotate Clock Wise", TetrisInstruction::RotateClock),
-            generate_single_key_bind_entry("Store Active Piece", TetrisInstruction::Store),
+            Text::new(" + "),
         ],
     )
 }
 
-/// A marker which marks the paused screen top div. 
-#[derive(Component)]
-pub struct PausedTopDiv;
-
-/// Differentiates which key for a specific action is meant. The value it holds theoretically 
-/// suppoers u32 many different keybinds but in practice only two are used. This is simply
-/// future proofing for when unlimited keys for a single action are allowed. 
-#[derive(Component)]
-pub struct KeyBindClickArea(u32);
+/// Refills the key bind lists of the key bind settings with the bindings found in the settings, whenever 
+/// they change or the lists are new. 
+fn display_key_bind_lists(
+    list_query: Query<(Entity, &TetrisInstruction, &KeyBindPlayer, Ref<KeyBindList>)>,
+    settings: Res<Settings>,
+    mut commands: Commands,
+) {
+    for (entity, instruction, player, list) in list_query {
+        if !settings.is_changed() && !list.is_added() {
+            continue;
+        }
 
-/// A marker which marks the text of the music volume. 
+        let bindings = &settings.player_key_binds(player.0).get(instruction).bindings;
+        commands.entity(entity).despawn_related::<Children>();
+        for (index, binding) in bindings.iter().enumerate() {
+            commands.entity(entity).with_child(generate_key_bind_button(*binding, index, *instruction, *player));
+        }
+        commands.entity(entity).with_child(generate_add_key_bind_button(bindings.len(), *instruction, *player));
+    }
+}
+
+/// Creates the UI component of the entire key bind settings, with the key binds of both players of a 
+/// versus game side by side. 
+fn generate_key_bind_menu() -> impl Bundle + use<> {
+    (
+        Node {
+            flex_direction: FlexDirection::Row,
+            align_content: AlignContent::FlexStart,
+            ..Default::default()
+        },
+        PauseMenuRemovableChildren,
+        children![
+            generate_player_key_bind_menu("Player 1", 0),
+            generate_player_key_bind_menu("Player 2 (Versus)", 1),
+        ],
+    )
+}
+
+/// Creates the UI component of the key bind settings of a single player. 
+fn generate_player_key_bind_menu(title: &str, player: usize) -> impl Bundle + use<> {
+    (
+        Node {
+            flex_direction: FlexDirection::Column,
+            align_content: AlignContent::FlexStart,
+            ..Default::default()
+        },
+        children![
+            (
+                Node {
+                    margin: UiRect::all(Val::Px(5.0)),
+                    ..Default::default()
+                },
+                Text::new(title),
+            ),
+            (
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    ..Default::default()
+                },
+                children![
+                    generate_single_key_bind_entry("Drop", TetrisInstruction::Drop, player),
+                    generate_single_key_bind_entry("Full Drop", TetrisInstruction::FullDrop, player),
+                    generate_single_key_bind_entry("Left", TetrisInstruction::Left, player),
+                    generate_single_key_bind_entry("Right", TetrisInstruction::Right, player),
+                    generate_single_key_bind_entry("Rotate Counter Clock Wise", TetrisInstruction::RotateCounter, player),
+                    generate_single_key_bind_entry("Rotate Clock Wise", TetrisInstruction::RotateClock, player),
+                    generate_single_key_bind_entry("Rotate 180°", TetrisInstruction::Rotate180, player),
+                    generate_single_key_bind_entry("Store Active Piece", TetrisInstruction::Store, player),
+                    generate_single_key_bind_entry("Forfeit", TetrisInstruction::Forfeit, player),
+                ],
+            ),
+            (
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    ..Default::default()
+                },
+                children![
+                    generate_single_key_bind_entry("Pause", TetrisInstruction::Pause, player),
+                    generate_single_key_bind_entry("Quick Restart (hold)", TetrisInstruction::QuickRestart, player),
+                    generate_single_key_bind_entry("Toggle Ghost Piece", TetrisInstruction::ToggleGhost, player),
+                ],
+            ),
+            generate_key_bind_preset_entry(player),
+        ],
+    )
+}
+
+/// Creates the UI components of the buttons replacing all key binds of the given player with a preset 
+/// or the defaults of that player. 
+fn generate_key_bind_preset_entry(player: usize) -> impl Bundle + use<> {
+    let player = KeyBindPlayer(player);
+    (
+        Node {
+            flex_direction: FlexDirection::Row,
+            margin: UiRect::all(Val::Px(5.0)),
+            padding: UiRect::all(Val::Px(5.0)),
+            ..Default::default()
+        },
+        BackgroundColor(Color::srgb(0.3, 0.3, 0.3)),
+        children![
+            (
+                Node {
+                    align_self: AlignSelf::Center,
+                    ..Default::default()
+                },
+                Text::new("Presets: "),
+            ),
+            generate_key_bind_preset_button(" WASD ", KeyBindPresetButton(KeyBindPreset::Wasd), player),
+            generate_key_bind_preset_button(" Arrows ", KeyBindPresetButton(KeyBindPreset::Arrows), player),
+            generate_key_bind_preset_button(" Guideline ", KeyBindPresetButton(KeyBindPreset::Guideline), player),
+            generate_key_bind_preset_button(" Reset to Defaults ", ResetKeyBindsButton, player),
+        ],
+    )
+}
+
+/// Creates the UI component of a single button of the key bind presets. 
+fn generate_key_bind_preset_button<T>(text: &str, button_marker: T, player: KeyBindPlayer) -> impl Bundle + use<T> 
+where T: Component {
+    (
+        Node {
+            margin: UiRect::left(Val::Px(5.0)),
+            padding: UiRect::all(Val::Px(4.0)),
+            ..Default::default()
+        },
+        Button,
+        BackgroundColor(Color::srgb(0.5, 0.5, 0.5)),
+        button_marker,
+        player,
+        children![
+            Text::new(text),
+        ],
+    )
+}
+
+/// A marker which marks the paused screen top div. 
+#[derive(Component)]
+pub struct PausedTopDiv;
+
+/// Differentiates which binding of a specific action is meant by its index. The index behind the last 
+/// binding adds a new one. 
+#[derive(Component)]
+pub struct KeyBindClickArea(usize);
+
+/// Marks the button removing the binding with the given index of a specific action. 
+#[derive(Component)]
+pub struct RemoveKeyBindButton(usize);
+
+/// Marks the node holding the buttons of all bindings of a specific action. 
+#[derive(Component)]
+pub struct KeyBindList;
+
+/// Marks a button replacing all key binds of a player with the given preset. 
+#[derive(Component)]
+pub struct KeyBindPresetButton(KeyBindPreset);
+
+/// Marks the button resetting all key binds of a player to their defaults. 
+#[derive(Component)]
+pub struct ResetKeyBindsButton;
+
+/// Differentiates which player a key bind settings field belongs to. 
+#[derive(Component, Clone, Copy)]
+pub struct KeyBindPlayer(usize);
+
+/// A marker which marks the text of the music volume. 
 #[derive(Component)]
 pub struct MusicVolumeTextMarker;
 
@@ -560,11 +1166,141 @@ pub struct DecreaseMusicVolumeButton;
 #[derive(Component)]
 pub struct IncreaseMusicVolumeButton;
 
+/// A marker which marks the button cycling through the rotation systems. 
+#[derive(Component)]
+pub struct RotationSystemButton;
+
+/// A marker which marks the text of the selected rotation system. 
+#[derive(Component)]
+pub struct RotationSystemTextMarker;
+
+/// A marker which marks the button cycling through the randomizers. 
+#[derive(Component)]
+pub struct RandomizerButton;
+
+/// A marker which marks the text of the selected randomizer. 
+#[derive(Component)]
+pub struct RandomizerTextMarker;
+
+/// A marker which marks the button cycling through the scoring rules. 
+#[derive(Component)]
+pub struct ScoringRulesButton;
+
+/// A marker which marks the text of the selected scoring rules. 
+#[derive(Component)]
+pub struct ScoringRulesTextMarker;
+
+/// A marker which marks the button selecting the seed for editing. 
+#[derive(Component)]
+pub struct SeedButton;
+
+/// A marker which marks the text of the seed for the next game. 
+#[derive(Component)]
+pub struct SeedTextMarker;
+
+/// Marks the seed button while the seed is being typed in. 
+#[derive(Component)]
+pub struct EditingSeed;
+
+/// A marker which marks the buttons and the value text of the next pieces shown settings. 
+#[derive(Component, Clone, Copy)]
+pub struct PreviewCountMarker;
+
+/// A marker which marks the buttons and the value text of the lock delay settings. 
+#[derive(Component, Clone, Copy)]
+pub struct LockDelayMarker;
+
+/// A marker which marks the button cycling through the game modes. 
+#[derive(Component)]
+pub struct GameModeButton;
+
+/// A marker which marks the text of the selected game mode. 
+#[derive(Component)]
+pub struct GameModeTextMarker;
+
+/// A marker which marks the buttons and the value text of one entry of the garbage sent for line clears. 
+#[derive(Component, Clone, Copy)]
+pub enum AttackMarker {
+    /// The garbage for clearing the given number of lines minus one. 
+    Lines(usize),
+    /// The garbage for a full T-spin clearing the given number of lines minus one. 
+    TSpinLines(usize),
+    PerfectClear,
+}
+
+impl AttackMarker {
+    /// Returns the garbage of the marked entry of the attack table. 
+    fn value(self, attack: &engine::model::scoring::AttackTable) -> u32 {
+        match self {
+            Self::Lines(index) => attack.lines[index],
+            Self::TSpinLines(index) => attack.t_spin_lines[index],
+            Self::PerfectClear => attack.perfect_clear,
+        }
+    }
+
+    /// Returns the marked entry of the attack table to be changed. 
+    fn value_mut(self, attack: &mut engine::model::scoring::AttackTable) -> &mut u32 {
+        match self {
+            Self::Lines(index) => &mut attack.lines[index],
+            Self::TSpinLines(index) => &mut attack.t_spin_lines[index],
+            Self::PerfectClear => &mut attack.perfect_clear,
+        }
+    }
+}
+
+/// A marker which marks the buttons and the value text of the cheese rows settings. 
+#[derive(Component, Clone, Copy)]
+pub struct CheeseRowsMarker;
+
+/// A marker which marks the buttons and the value text of the delayed auto shift settings. 
+#[derive(Component, Clone, Copy)]
+pub struct DasMarker;
+
+/// A marker which marks the buttons and the value text of the auto repeat rate settings. 
+#[derive(Component, Clone, Copy)]
+pub struct ArrMarker;
+
+/// A marker which marks the buttons and the value text of the soft drop factor settings. 
+#[derive(Component, Clone, Copy)]
+pub struct SoftDropFactorMarker;
+
+/// A marker which marks the button cycling through the lock reset rules. 
+#[derive(Component)]
+pub struct LockResetButton;
+
+/// A marker which marks the text of the selected lock reset rule. 
+#[derive(Component)]
+pub struct LockResetTextMarker;
+
+/// A marker which marks the button cycling through the hold rules. 
+#[derive(Component)]
+pub struct HoldRuleButton;
+
+/// A marker which marks the text of the selected hold rule. 
+#[derive(Component)]
+pub struct HoldRuleTextMarker;
+
+/// Differentiates the two field dimensions in the gameplay settings. 
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldDimension {
+    Width,
+    Height,
+}
+
+/// Differentiates the decrease and increase buttons of a numeric settings entry. 
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SettingsChange {
+    Decrease,
+    Increase,
+}
+
 /// Differentiates the existing settings categories into their tabs.  
 #[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SettingsTab {
     Audio, 
     KeyMapping,
+    Gameplay,
+    Handling,
 }
 
 /// A marker which marks the audio settings button. 
@@ -575,18 +1311,18 @@ pub struct AudioButton;
 #[derive(Component)]
 pub struct KeyMappingButton;
 
-/// A marker which marks which part of the pause menu are children that can be removed 
-/// when switchting the active settings tab. 
+/// A marker which marks the gameplay settings button. 
 #[derive(Component)]
-pub struct PauseMenuRemovableChildren;
+pub struct GameplayButton;
 
-/// A marker which marks the text in the primary keybind button. 
+/// A marker which marks the handling settings button. 
 #[derive(Component)]
-pub struct FirstKeyBindTextMarker;
+pub struct HandlingButton;
 
-/// A marker which marks the text in the secondary keybind button. 
+/// A marker which marks which part of the pause menu are children that can be removed 
+/// when switchting the active settings tab. 
 #[derive(Component)]
-pub struct SecondKeyBindTextMarker;
+pub struct PauseMenuRemovableChildren;
 
 /// Removes the currently selected settings tab via [PauseMenuRemovableChildren]. 
 fn remove_settings_children(
@@ -668,241 +1404,1351 @@ fn key_mapping_button_listener(
     }
 }
 
+/// Implements the button functionality for selecting the gameplay settings tab.  
+fn gameplay_button_listener(
+    mut button_query: Query<(&Interaction, &mut bevy::ui::BackgroundColor), (Changed<Interaction>, With<Button>, With<GameplayButton>)>,
+    mut commands: Commands,
+    mut settings_tab_query: Query<&mut SettingsTab>,
+    paused_top_div_query: Query<Entity, With<PausedTopDiv>>,
+    remove_settings_query: Query<Entity, With<PauseMenuRemovableChildren>>,
+) {
+    let Ok((interaction, mut backgroud_color)) = button_query.single_mut() else {return;};
+
+    match interaction {
+        Interaction::Pressed => {
+            if let Ok(mut settings_tab) = settings_tab_query.single_mut() {
+                if *settings_tab == SettingsTab::Gameplay {
+                    return;
+                }
+                *settings_tab = SettingsTab::Gameplay;
+                remove_settings_children(remove_settings_query, &mut commands);
+            } else {
+                commands.spawn(SettingsTab::Gameplay);
+            }
+
+            let Ok(entity) = paused_top_div_query.single() else {return;};
+
+            commands.entity(entity).with_child(generate_gameplay_settings());
+        }
+        Interaction::Hovered => {
+            *backgroud_color = HOVERED_BUTTON_BACKGROUND_COLOR;
+        }
+        Interaction::None => {
+            *backgroud_color = EMPTY_BACKGROUND_COLOR;
+        }
+    }
+}
+
+/// Implements the button functionality for selecting the handling settings tab.  
+fn handling_button_listener(
+    mut button_query: Query<(&Interaction, &mut bevy::ui::BackgroundColor), (Changed<Interaction>, With<Button>, With<HandlingButton>)>,
+    mut commands: Commands,
+    mut settings_tab_query: Query<&mut SettingsTab>,
+    paused_top_div_query: Query<Entity, With<PausedTopDiv>>,
+    remove_settings_query: Query<Entity, With<PauseMenuRemovableChildren>>,
+) {
+    let Ok((interaction, mut backgroud_color)) = button_query.single_mut() else {return;};
+
+    match interaction {
+        Interaction::Pressed => {
+            if let Ok(mut settings_tab) = settings_tab_query.single_mut() {
+                if *settings_tab == SettingsTab::Handling {
+                    return;
+                }
+                *settings_tab = SettingsTab::Handling;
+                remove_settings_children(remove_settings_query, &mut commands);
+            } else {
+                commands.spawn(SettingsTab::Handling);
+            }
+
+            let Ok(entity) = paused_top_div_query.single() else {return;};
+
+            commands.entity(entity).with_child(generate_handling_settings());
+        }
+        Interaction::Hovered => {
+            *backgroud_color = HOVERED_BUTTON_BACKGROUND_COLOR;
+        }
+        Interaction::None => {
+            *backgroud_color = EMPTY_BACKGROUND_COLOR;
+        }
+    }
+}
+
 /// Implements the button functionality for starting a new game. 
 fn new_game_button_listener(
     mut button_query: Query<(&Interaction, &mut bevy::ui::BackgroundColor), (Changed<Interaction>, With<Button>, With<NewGameButton>)>, 
-    mut game_query: Query<&mut engine::scene::Game>,
-    mut game_score: ResMut<engine::scene::GameScore>,
+    board_query: Query<Entity, With<engine::scene::Board>>,
     mut is_game_running: ResMut<engine::scene::IsAppRunning>,
     main_div_query: Query<Entity, With<NewGameTopDiv>>,
+    settings: Res<Settings>,
     mut commands: Commands, 
 ) {
     let Ok((interaction, mut background_color)) = button_query.single_mut() else {return;};
 
     match interaction {
         Interaction::Pressed => {
-            //reset the playfield
-            let Ok(mut game) = game_query.single_mut() else {return;};
-            game.tetris = engine::model::Tetris::default();
-            
-            //reset the score
-            *game_score = engine::scene::GameScore::default();
+            //replace the boards along with their scores
+            for board in board_query {
+                commands.entity(board).despawn();
+            }
+            engine::scene::spawn_boards(&mut commands, &settings);
+
+            //remove game over screen
+            let Ok(main_div) = main_div_query.single() else {error!("Failed to remove New Game main div!"); return;};
+            commands.entity(main_div).despawn();
+
+            //set game to be running again
+            is_game_running.0 = engine::scene::AppState::Running;
+        }
+        Interaction::Hovered => {
+            *background_color = BackgroundColor(Color::Srgba(Srgba { red: 1.0, green: 1.0, blue: 1.0, alpha: 0.2 }));
+            //*background_color = BackgroundColor(Color::Srgba(Srgba::WHITE));
+        }
+        Interaction::None => {
+            *background_color = EMPTY_BACKGROUND_COLOR;
+        }
+    }
+}
+
+/// Implements the button functionality for watching one of the saved replays. 
+fn watch_replay_button_listener(
+    button_query: Query<(&Interaction, &mut bevy::ui::BackgroundColor, &WatchReplayButton), (Changed<Interaction>, With<Button>)>, 
+    board_query: Query<Entity, With<engine::scene::Board>>,
+    mut is_game_running: ResMut<engine::scene::IsAppRunning>,
+    main_div_query: Query<Entity, With<NewGameTopDiv>>,
+    mut commands: Commands, 
+) {
+    for (interaction, mut background_color, button) in button_query {
+        match interaction {
+            Interaction::Pressed => {
+                let replay = match engine::replay::Replay::new_from_serialized(&button.0) {
+                    Ok(replay) => replay,
+                    Err(err) => {
+                        error!("Could not load the replay. Error: {}", err);
+                        continue;
+                    }
+                };
+
+                //replace the boards with a single board at the start of the replay
+                for board in &board_query {
+                    commands.entity(board).despawn();
+                }
+                let board = engine::scene::spawn_board(&mut commands, replay.game(), 0, 1);
+                commands.entity(board).insert(engine::replay::Playback::new(replay));
+
+                //remove game over screen
+                let Ok(main_div) = main_div_query.single() else {error!("Failed to remove New Game main div!"); continue;};
+                commands.entity(main_div).despawn();
+
+                //set game to be running again
+                is_game_running.0 = engine::scene::AppState::Running;
+            }
+            Interaction::Hovered => {
+                *background_color = HOVERED_BUTTON_BACKGROUND_COLOR;
+            }
+            Interaction::None => {
+                *background_color = EMPTY_BACKGROUND_COLOR;
+            }
+        }
+    }
+}
+
+/// Implements the button functionality for resuming the last game, which has already been loaded 
+/// behind the resume screen. 
+fn resume_game_button_listener(
+    mut button_query: Query<(&Interaction, &mut bevy::ui::BackgroundColor), (Changed<Interaction>, With<Button>, With<ResumeGameButton>)>, 
+    mut is_game_running: ResMut<engine::scene::IsAppRunning>,
+    main_div_query: Query<Entity, With<NewGameTopDiv>>,
+    mut commands: Commands, 
+) {
+    let Ok((interaction, mut background_color)) = button_query.single_mut() else {return;};
+
+    match interaction {
+        Interaction::Pressed => {
+            //remove resume screen
+            let Ok(main_div) = main_div_query.single() else {error!("Failed to remove Resume Game main div!"); return;};
+            commands.entity(main_div).despawn();
+
+            //set game to be running again
+            is_game_running.0 = engine::scene::AppState::Running;
+        }
+        Interaction::Hovered => {
+            *background_color = HOVERED_BUTTON_BACKGROUND_COLOR;
+        }
+        Interaction::None => {
+            *background_color = EMPTY_BACKGROUND_COLOR;
+        }
+    }
+}
+
+/// Implements the button functionality for a single keybind area. 
+fn individual_keybind_button_listener(
+    mut button_query: Query<(&TetrisInstruction, &mut BackgroundColor, &Interaction, &KeyBindClickArea, &KeyBindPlayer), (Changed<Interaction>, With<Button>)>,
+    mut commands: Commands,
+    waiting_query: Query<Entity, With<WaitingForNewKeyBind>>,
+    clear_all: Res<ClearKeybindClicks>,
+    highlight_one: Res<HighlghtClickedKeybind>,
+) {
+    for (instruction, mut background_color, interaction, keybind_click_area, player) in &mut button_query {
+        if *interaction == Interaction::Pressed{
+            //remove previous waiting component
+            if let Ok(entity) = waiting_query.single() {    //theoretically, no more than one should ever exist concurrently
+                commands.entity(entity).despawn();
+            }
+
+            //change the color to signal to the user which field is active
+            background_color.0 = Color::srgb(0.5, 0.5, 0.5);
+
+            //spawn a struct which signals not to use button inputs
+            commands.spawn(WaitingForNewKeyBind {
+                index: keybind_click_area.0,
+                selected_tetris_instruction: *instruction,
+                player: player.0,
+            });
+            commands.run_system(clear_all.0);
+            commands.run_system(highlight_one.0);
+        }
+    }
+}
+
+/// Implements the button functionality for removing a single binding. 
+fn remove_keybind_button_listener(
+    button_query: Query<(&Interaction, &TetrisInstruction, &KeyBindPlayer, &RemoveKeyBindButton), (Changed<Interaction>, With<Button>)>,
+    waiting_query: Query<Entity, With<WaitingForNewKeyBind>>,
+    mut settings: ResMut<Settings>,
+    mut commands: Commands,
+) {
+    for (interaction, instruction, player, remove_button) in button_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        //the indices of the bindings change, so a binding waiting to be replaced is cancelled
+        for entity in waiting_query {
+            commands.entity(entity).despawn();
+        }
+
+        let bindings = &mut settings.player_key_binds_mut(player.0).get_mut(instruction).bindings;
+        //without a pause binding, a paused game could not be resumed anymore
+        if *instruction == TetrisInstruction::Pause && bindings.len() == 1 {
+            warn!("The last pause binding of player {} can not be removed.", player.0 + 1);
+            continue;
+        }
+        if remove_button.0 < bindings.len() {
+            bindings.remove(remove_button.0);
+        }
+
+        if let Err(err) = settings.write_to_file() {
+            error!("Could not save current settings. Error: {}", err);
+        }
+    }
+}
+
+/// Implements the button functionality for the key bind preset buttons. 
+fn key_bind_preset_button_listener(
+    button_query: Query<(&Interaction, &KeyBindPresetButton, &KeyBindPlayer), (Changed<Interaction>, With<Button>)>,
+    mut settings: ResMut<Settings>,
+) {
+    for (interaction, preset, player) in button_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        settings.set_key_binds(player.0, preset.0.key_binds());
+
+        if let Err(err) = settings.write_to_file() {
+            error!("Could not save current settings. Error: {}", err);
+        }
+    }
+}
+
+/// Implements the button functionality for resetting the key binds of a player. 
+fn reset_key_binds_button_listener(
+    button_query: Query<(&Interaction, &KeyBindPlayer), (Changed<Interaction>, With<Button>, With<ResetKeyBindsButton>)>,
+    mut settings: ResMut<Settings>,
+) {
+    for (interaction, player) in button_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let key_binds = if player.0 == 0 {KeyBinds::default()} else {KeyBinds::versus_default()};
+        settings.set_key_binds(player.0, key_binds);
+
+        if let Err(err) = settings.write_to_file() {
+            error!("Could not save current settings. Error: {}", err);
+        }
+    }
+}
+
+/// The struct that holds the general settings of the bevy engine game: audio, 
+/// keybinds, handling, the number of shown next pieces, whether the ghost piece is shown and the rules and 
+/// seed for the next game. A seed of None starts every game with a random seed. 
+#[derive(Resource, Clone, Debug)]
+pub struct Settings {
+    pub music_volume: f32,
+    pub key_binds: KeyBinds,
+    pub rules: engine::model::TetrisRules,
+    pub preview_count: u32,
+    pub seed: Option<u64>,
+    pub game_mode: engine::scene::GameMode,
+    /// The key binds of the second player of a versus game. 
+    pub versus_key_binds: KeyBinds,
+    pub handling: Handling,
+    pub ghost_visible: bool,
+}
+
+impl Settings {
+    /// Returns the key binds of the given player. 
+    pub fn player_key_binds(&self, player: usize) -> &KeyBinds {
+        if player == 0 {&self.key_binds} else {&self.versus_key_binds}
+    }
+
+    /// Returns the mutable key binds of the given player. 
+    pub fn player_key_binds_mut(&mut self, player: usize) -> &mut KeyBinds {
+        if player == 0 {&mut self.key_binds} else {&mut self.versus_key_binds}
+    }
+
+    /// Binds the given input to the instruction of the given player, replacing the binding with the 
+    /// given index or adding it if the index is behind the last binding. An input already bound to a 
+    /// different instruction of the player is swapped with the replaced binding, or removed there if 
+    /// nothing is replaced. Keys are shared by all players, so the same applies to the keys of the 
+    /// other player. The last pause binding of a player is never removed, in that case nothing is bound. 
+    pub fn bind(&mut self, player: usize, instruction: TetrisInstruction, index: usize, binding: InputBinding) {
+        let replaced = self.player_key_binds(player).get(&instruction).bindings.get(index).copied();
+
+        //without a pause binding, a paused game could not be resumed anymore
+        let takes_last_pause_binding = (0..engine::scene::GameMode::Versus.players())
+            .filter(|other_player| *other_player != player || instruction != TetrisInstruction::Pause)
+            .filter(|other_player| *other_player == player || matches!(binding, InputBinding::Key(_)))
+            .find(|other_player| self.player_key_binds(*other_player).get(&TetrisInstruction::Pause).bindings == [binding]);
+        if replaced.is_none() && let Some(other_player) = takes_last_pause_binding {
+            warn!("{} is the last pause binding of player {}, it can not be bound to {:?}.", binding_to_str(binding), other_player + 1, instruction);
+            return;
+        }
+
+        for other_player in 0..engine::scene::GameMode::Versus.players() {
+            if other_player != player && !matches!(binding, InputBinding::Key(_)) {
+                continue;
+            }
+
+            for key_bind in &mut self.player_key_binds_mut(other_player).key_binds {
+                if other_player == player && key_bind.action == instruction {
+                    continue;
+                }
+                let Some(position) = key_bind.bindings.iter().position(|b| *b == binding) else {continue;};
+
+                warn!(
+                    "{} was already bound to {:?} of player {}, {}.", 
+                    binding_to_str(binding), 
+                    key_bind.action, 
+                    other_player + 1, 
+                    if replaced.is_some() {"swapping the bindings"} else {"removing it there"},
+                );
+                match replaced {
+                    Some(replaced) => key_bind.bindings[position] = replaced,
+                    None => {
+                        key_bind.bindings.remove(position);
+                    }
+                }
+            }
+        }
+
+        let bindings = &mut self.player_key_binds_mut(player).get_mut(&instruction).bindings;
+        if index < bindings.len() {
+            bindings[index] = binding;
+        } else if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        //the binding may already have been bound to the same instruction at another index
+        let mut seen = HashSet::new();
+        bindings.retain(|b| seen.insert(*b));
+    }
+
+    /// Replaces all key binds of the given player. Keys which are also bound by the other player are 
+    /// removed there, unless it is the last pause binding of the other player, which is left out of the 
+    /// new key binds instead. 
+    pub fn set_key_binds(&mut self, player: usize, mut key_binds: KeyBinds) {
+        let last_pause_keys: Vec<InputBinding> = (0..engine::scene::GameMode::Versus.players())
+            .filter(|other_player| *other_player != player)
+            .filter_map(|other_player| match self.player_key_binds(other_player).get(&TetrisInstruction::Pause).bindings[..] {
+                [binding @ InputBinding::Key(_)] => Some(binding),
+                _ => None,
+            })
+            .collect();
+        for key_bind in &mut key_binds.key_binds {
+            key_bind.bindings.retain(|binding| {
+                let conflict = last_pause_keys.contains(binding);
+                if conflict {
+                    warn!("{} is the last pause binding of the other player, leaving it out of {:?}.", binding_to_str(*binding), key_bind.action);
+                }
+                !conflict
+            });
+        }
+
+        let keys = bound_keys(&key_binds.key_binds);
+        *self.player_key_binds_mut(player) = key_binds;
+
+        for other_player in (0..engine::scene::GameMode::Versus.players()).filter(|p| *p != player) {
+            for key_bind in &mut self.player_key_binds_mut(other_player).key_binds {
+                key_bind.bindings.retain(|binding| {
+                    let conflict = keys.contains(binding);
+                    if conflict {
+                        warn!("{} is now bound by player {}, removing it from {:?}.", binding_to_str(*binding), player + 1, key_bind.action);
+                    }
+                    !conflict
+                });
+            }
+        }
+    }
+
+    /// Tries to save the settings to the data/settings.dat file. Returns Err 
+    /// if any problems appear. 
+    pub(crate) fn write_to_file(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let _ = std::fs::create_dir("./data");
+
+        //get a file 
+        let file = std::fs::File::create("data/settings.dat")?;
+
+        //serialize and write to file
+        serde_cbor::to_writer(file, &self)?;
+
+        Ok(())
+    }
+
+    /// Reads the settings from the data/settings.dat file. Returns Err if
+    /// any problems appear. 
+    fn new_from_serialized() -> Result<Self, Box<dyn std::error::Error>> {
+        //open the file
+        let file = std::fs::File::open("data/settings.dat")?;
+
+        //deserialze 
+        let settings = serde_cbor::from_reader(file)?;
+
+        Ok(settings)
+    }
+
+    /// Reads the settings from the file. If this fails (as it does when first
+    /// starting the game), uses default values instead. 
+    fn from_serialized_or_default() -> Self {
+        //try to 
+        let attempt = Settings::new_from_serialized();
+        match attempt {
+            Ok(settings) => {
+                return settings;
+            }
+            Err(err) => {
+                warn!("Could not load user settings. Using default instead. Error: {}", err);
+            }
+        }
+
+        Settings::default()
+    }
+}
+
+impl Serialize for Settings {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where S: serde::Serializer {
+        let mut state = serializer.serialize_struct("Settings", 9)?;    //len = nbr of fields to be serialized
+
+        state.serialize_field("music_volume", &self.music_volume)?;
+        
+        let serializable_key_binds = KeyBindsSerialized::from(self.key_binds.clone());
+        state.serialize_field("key_binds", &serializable_key_binds)?;
+
+        state.serialize_field("rules", &self.rules)?;
+
+        state.serialize_field("preview_count", &self.preview_count)?;
+
+        state.serialize_field("seed", &self.seed)?;
+
+        state.serialize_field("game_mode", &self.game_mode)?;
+
+        let serializable_versus_key_binds = KeyBindsSerialized::from(self.versus_key_binds.clone());
+        state.serialize_field("versus_key_binds", &serializable_versus_key_binds)?;
+
+        state.serialize_field("handling", &self.handling)?;
+
+        state.serialize_field("ghost_visible", &self.ghost_visible)?;
+
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Settings {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where D: serde::Deserializer<'de> {
+        #[derive(Deserialize, Debug)]
+        struct Helper {
+            music_volume: f32,
+            key_binds: KeyBindsSerialized,
+            #[serde(default)]
+            rules: engine::model::TetrisRules,
+            #[serde(default = "default_preview_count")]
+            preview_count: u32,
+            #[serde(default)]
+            seed: Option<u64>,
+            #[serde(default)]
+            game_mode: engine::scene::GameMode,
+            #[serde(default)]
+            versus_key_binds: Option<KeyBindsSerialized>,
+            #[serde(default)]
+            handling: Handling,
+            #[serde(default = "default_ghost_visible")]
+            ghost_visible: bool,
+        }
+
+        fn default_preview_count() -> u32 {
+            PREVIEW_COUNT_DEFAULT
+        }
+
+        fn default_ghost_visible() -> bool {
+            true
+        }
+
+        let helper = Helper::deserialize(deserializer)?;
+
+        //keys are shared by both players, so the defaults of new instructions must not take keys of the other player
+        let versus_key_binds = helper.versus_key_binds.unwrap_or(KeyBindsSerialized {key_binds: Vec::new()});
+        let key_binds = helper.key_binds.with_defaults(KeyBinds::default(), &bound_keys(&versus_key_binds.key_binds));
+        let versus_key_binds = versus_key_binds.with_defaults(KeyBinds::versus_default(), &bound_keys(&key_binds.key_binds));
+
+        Ok(Settings {
+            music_volume: helper.music_volume,
+            key_binds,
+            rules: helper.rules,
+            preview_count: helper.preview_count,
+            seed: helper.seed,
+            game_mode: helper.game_mode,
+            versus_key_binds,
+            handling: helper.handling,
+            ghost_visible: helper.ghost_visible,
+        })
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self { 
+            music_volume: 0.5,
+            key_binds: KeyBinds::default(),
+            rules: engine::model::TetrisRules::default(),
+            preview_count: PREVIEW_COUNT_DEFAULT,
+            seed: None,
+            game_mode: engine::scene::GameMode::default(),
+            versus_key_binds: KeyBinds::versus_default(),
+            handling: Handling::default(),
+            ghost_visible: true,
+        }
+    }
+}
+
+/// Updates the displayed volume text. 
+fn display_music_volume_settings(
+    settings: Res<Settings>,
+    mut text_query: Query<&mut Text, With<MusicVolumeTextMarker>>,
+) {
+    let Ok(mut text) = text_query.single_mut() else {return;};
+
+    *text = Text::new(format!(" {:.0} ", settings.music_volume * 100.0));
+}
+
+/// Implements the button functionalities for the decrease and increase music volume button. s
+fn update_music_volume_settings(
+    mut settings: ResMut<Settings>,
+    decrease_query: Query<&Interaction, (Changed<Interaction>, With<Button>, With<DecreaseMusicVolumeButton>)>,
+    increase_query: Query<&Interaction, (Changed<Interaction>, With<Button>, With<IncreaseMusicVolumeButton>)>,
+) {
+    let mut changed = false;
+
+    'decrease: {
+        let Ok(interaction) = decrease_query.single() else {break 'decrease};
+
+        if *interaction == Interaction::Pressed {
+            settings.music_volume = (settings.music_volume - 0.1).max(0.0);
+            changed = true;
+        }
+    }
+
+    'increase: {
+        let Ok(interaction) = increase_query.single() else {break 'increase};
+
+        if *interaction == Interaction::Pressed {
+            settings.music_volume = (settings.music_volume + 0.1).min(1.0);
+            changed = true;
+        }
+    }
+
+    if changed
+            && let Err(err) = settings.write_to_file() {
+        error!("Could not save current settings. Error: {}", err);
+    }
+}
+
+/// Updates the displayed rotation system text. 
+fn display_rotation_system_settings(
+    settings: Res<Settings>,
+    mut text_query: Query<&mut Text, With<RotationSystemTextMarker>>,
+) {
+    let Ok(mut text) = text_query.single_mut() else {return;};
+
+    *text = Text::new(rotation_system_to_str(settings.rules.rotation_system));
+}
+
+/// Implements the button functionality for cycling through the rotation systems. 
+fn update_rotation_system_settings(
+    mut settings: ResMut<Settings>,
+    button_query: Query<&Interaction, (Changed<Interaction>, With<Button>, With<RotationSystemButton>)>,
+) {
+    let Ok(interaction) = button_query.single() else {return;};
+
+    if *interaction == Interaction::Pressed {
+        let all = engine::model::RotationSystemKind::all_rotation_system_array();
+        let index = all.iter().position(|kind| *kind == settings.rules.rotation_system).unwrap_or(0);
+        settings.rules.rotation_system = all[(index + 1) % all.len()];
+
+        if let Err(err) = settings.write_to_file() {
+            error!("Could not save current settings. Error: {}", err);
+        }
+    }
+}
+
+/// Updates the displayed randomizer text. 
+fn display_randomizer_settings(
+    settings: Res<Settings>,
+    mut text_query: Query<&mut Text, With<RandomizerTextMarker>>,
+) {
+    let Ok(mut text) = text_query.single_mut() else {return;};
+
+    *text = Text::new(randomizer_to_str(settings.rules.randomizer));
+}
+
+/// Implements the button functionality for cycling through the randomizers. 
+fn update_randomizer_settings(
+    mut settings: ResMut<Settings>,
+    button_query: Query<&Interaction, (Changed<Interaction>, With<Button>, With<RandomizerButton>)>,
+) {
+    let Ok(interaction) = button_query.single() else {return;};
+
+    if *interaction == Interaction::Pressed {
+        let all = engine::model::randomizer::RandomizerKind::all_randomizer_array();
+        let index = all.iter().position(|kind| *kind == settings.rules.randomizer).unwrap_or(0);
+        settings.rules.randomizer = all[(index + 1) % all.len()];
+
+        if let Err(err) = settings.write_to_file() {
+            error!("Could not save current settings. Error: {}", err);
+        }
+    }
+}
+
+/// Updates the displayed scoring rules text. 
+fn display_scoring_rules_settings(
+    settings: Res<Settings>,
+    mut text_query: Query<&mut Text, With<ScoringRulesTextMarker>>,
+) {
+    let Ok(mut text) = text_query.single_mut() else {return;};
+
+    *text = Text::new(scoring_rules_to_str(settings.rules.scoring));
+}
+
+/// Implements the button functionality for cycling through the scoring rules. 
+fn update_scoring_rules_settings(
+    mut settings: ResMut<Settings>,
+    button_query: Query<&Interaction, (Changed<Interaction>, With<Button>, With<ScoringRulesButton>)>,
+) {
+    let Ok(interaction) = button_query.single() else {return;};
+
+    if *interaction == Interaction::Pressed {
+        let all = engine::model::scoring::ScoringRules::all_scoring_rules_array();
+        let index = all.iter().position(|rules| *rules == settings.rules.scoring).unwrap_or(0);
+        settings.rules.scoring = all[(index + 1) % all.len()];
+
+        if let Err(err) = settings.write_to_file() {
+            error!("Could not save current settings. Error: {}", err);
+        }
+    }
+}
+
+/// Updates the displayed number of shown next pieces. 
+fn display_preview_count_settings(
+    settings: Res<Settings>,
+    text_query: Query<&mut Text, With<PreviewCountMarker>>,
+) {
+    for mut text in text_query {
+        *text = Text::new(format!(" {} ", settings.preview_count));
+    }
+}
+
+/// Implements the button functionalities for the decrease and increase shown next pieces buttons. 
+fn update_preview_count_settings(
+    mut settings: ResMut<Settings>,
+    button_query: Query<(&Interaction, &SettingsChange), (Changed<Interaction>, With<Button>, With<PreviewCountMarker>)>,
+) {
+    let mut changed = false;
+
+    for (interaction, change) in button_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let value = &mut settings.preview_count;
+        *value = match change {
+            SettingsChange::Decrease => value.saturating_sub(1).max(1),
+            SettingsChange::Increase => (*value + 1).min(engine::model::MAX_PREVIEW_COUNT),
+        };
+        changed = true;
+    }
+
+    if changed
+            && let Err(err) = settings.write_to_file() {
+        error!("Could not save current settings. Error: {}", err);
+    }
+}
+
+/// Updates the displayed lock delay text. 
+fn display_lock_delay_settings(
+    settings: Res<Settings>,
+    text_query: Query<&mut Text, With<LockDelayMarker>>,
+) {
+    for mut text in text_query {
+        *text = Text::new(format!(" {} ", settings.rules.lock_delay_millis));
+    }
+}
+
+/// Implements the button functionalities for the decrease and increase lock delay buttons. 
+fn update_lock_delay_settings(
+    mut settings: ResMut<Settings>,
+    button_query: Query<(&Interaction, &SettingsChange), (Changed<Interaction>, With<Button>, With<LockDelayMarker>)>,
+) {
+    let mut changed = false;
+
+    for (interaction, change) in button_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let value = &mut settings.rules.lock_delay_millis;
+        *value = match change {
+            SettingsChange::Decrease => value.saturating_sub(LOCK_DELAY_STEP_MILLIS),
+            SettingsChange::Increase => (*value + LOCK_DELAY_STEP_MILLIS).min(LOCK_DELAY_MAX_MILLIS),
+        };
+        changed = true;
+    }
+
+    if changed
+            && let Err(err) = settings.write_to_file() {
+        error!("Could not save current settings. Error: {}", err);
+    }
+}
+
+/// Updates the displayed delayed auto shift text. 
+fn display_das_settings(
+    settings: Res<Settings>,
+    text_query: Query<&mut Text, With<DasMarker>>,
+) {
+    for mut text in text_query {
+        *text = Text::new(format!(" {} ", settings.handling.das_millis));
+    }
+}
+
+/// Implements the button functionalities for the decrease and increase delayed auto shift buttons. 
+fn update_das_settings(
+    mut settings: ResMut<Settings>,
+    button_query: Query<(&Interaction, &SettingsChange), (Changed<Interaction>, With<Button>, With<DasMarker>)>,
+) {
+    let mut changed = false;
+
+    for (interaction, change) in button_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let value = &mut settings.handling.das_millis;
+        *value = match change {
+            SettingsChange::Decrease => value.saturating_sub(DAS_STEP_MILLIS),
+            SettingsChange::Increase => (*value + DAS_STEP_MILLIS).min(DAS_MAX_MILLIS),
+        };
+        changed = true;
+    }
+
+    if changed
+            && let Err(err) = settings.write_to_file() {
+        error!("Could not save current settings. Error: {}", err);
+    }
+}
+
+/// Updates the displayed auto repeat rate text. 
+fn display_arr_settings(
+    settings: Res<Settings>,
+    text_query: Query<&mut Text, With<ArrMarker>>,
+) {
+    for mut text in text_query {
+        *text = Text::new(format!(" {} ", settings.handling.arr_millis));
+    }
+}
+
+/// Implements the button functionalities for the decrease and increase auto repeat rate buttons. 
+fn update_arr_settings(
+    mut settings: ResMut<Settings>,
+    button_query: Query<(&Interaction, &SettingsChange), (Changed<Interaction>, With<Button>, With<ArrMarker>)>,
+) {
+    let mut changed = false;
+
+    for (interaction, change) in button_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let value = &mut settings.handling.arr_millis;
+        *value = match change {
+            SettingsChange::Decrease => value.saturating_sub(ARR_STEP_MILLIS),
+            SettingsChange::Increase => (*value + ARR_STEP_MILLIS).min(ARR_MAX_MILLIS),
+        };
+        changed = true;
+    }
+
+    if changed
+            && let Err(err) = settings.write_to_file() {
+        error!("Could not save current settings. Error: {}", err);
+    }
+}
+
+/// Updates the displayed soft drop factor text. 
+fn display_soft_drop_factor_settings(
+    settings: Res<Settings>,
+    text_query: Query<&mut Text, With<SoftDropFactorMarker>>,
+) {
+    for mut text in text_query {
+        *text = Text::new(format!(" {} ", settings.handling.soft_drop_factor));
+    }
+}
+
+/// Implements the button functionalities for the decrease and increase soft drop factor buttons. 
+fn update_soft_drop_factor_settings(
+    mut settings: ResMut<Settings>,
+    button_query: Query<(&Interaction, &SettingsChange), (Changed<Interaction>, With<Button>, With<SoftDropFactorMarker>)>,
+) {
+    let mut changed = false;
+
+    for (interaction, change) in button_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let value = &mut settings.handling.soft_drop_factor;
+        *value = match change {
+            SettingsChange::Decrease => value.saturating_sub(1).max(1),
+            SettingsChange::Increase => (*value + 1).min(SOFT_DROP_FACTOR_MAX),
+        };
+        changed = true;
+    }
+
+    if changed
+            && let Err(err) = settings.write_to_file() {
+        error!("Could not save current settings. Error: {}", err);
+    }
+}
+
+/// Updates the displayed game mode text. 
+fn display_game_mode_settings(
+    settings: Res<Settings>,
+    mut text_query: Query<&mut Text, With<GameModeTextMarker>>,
+) {
+    let Ok(mut text) = text_query.single_mut() else {return;};
+
+    *text = Text::new(game_mode_to_str(settings.game_mode));
+}
+
+/// Implements the button functionality for cycling through the game modes. 
+fn update_game_mode_settings(
+    mut settings: ResMut<Settings>,
+    button_query: Query<&Interaction, (Changed<Interaction>, With<Button>, With<GameModeButton>)>,
+) {
+    let Ok(interaction) = button_query.single() else {return;};
+
+    if *interaction == Interaction::Pressed {
+        let all = engine::scene::GameMode::all_game_mode_array();
+        let index = all.iter().position(|mode| *mode == settings.game_mode).unwrap_or(0);
+        settings.game_mode = all[(index + 1) % all.len()];
+
+        if let Err(err) = settings.write_to_file() {
+            error!("Could not save current settings. Error: {}", err);
+        }
+    }
+}
+
+/// Updates the displayed texts of the garbage sent for line clears, T-spins and perfect clears. 
+fn display_attack_settings(
+    settings: Res<Settings>,
+    text_query: Query<(&AttackMarker, &mut Text)>,
+) {
+    for (marker, mut text) in text_query {
+        *text = Text::new(format!(" {} ", marker.value(&settings.rules.attack)));
+    }
+}
+
+/// Implements the button functionalities for the decrease and increase buttons of the garbage sent 
+/// for line clears, T-spins and perfect clears. 
+fn update_attack_settings(
+    mut settings: ResMut<Settings>,
+    button_query: Query<(&Interaction, &SettingsChange, &AttackMarker), (Changed<Interaction>, With<Button>)>,
+) {
+    let mut changed = false;
+
+    for (interaction, change, marker) in button_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let value = marker.value_mut(&mut settings.rules.attack);
+        *value = match change {
+            SettingsChange::Decrease => value.saturating_sub(1),
+            SettingsChange::Increase => (*value + 1).min(ATTACK_MAX_ROWS),
+        };
+        changed = true;
+    }
+
+    if changed
+            && let Err(err) = settings.write_to_file() {
+        error!("Could not save current settings. Error: {}", err);
+    }
+}
+
+/// Updates the displayed cheese rows text. 
+fn display_cheese_rows_settings(
+    settings: Res<Settings>,
+    text_query: Query<&mut Text, With<CheeseRowsMarker>>,
+) {
+    for mut text in text_query {
+        *text = Text::new(format!(" {} ", settings.rules.cheese_rows));
+    }
+}
+
+/// Implements the button functionalities for the decrease and increase cheese rows buttons. At most 
+/// half of the field can be filled with cheese. 
+fn update_cheese_rows_settings(
+    mut settings: ResMut<Settings>,
+    button_query: Query<(&Interaction, &SettingsChange), (Changed<Interaction>, With<Button>, With<CheeseRowsMarker>)>,
+) {
+    let mut changed = false;
+
+    for (interaction, change) in button_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let max = settings.rules.field_height / 2;
+        let value = &mut settings.rules.cheese_rows;
+        *value = match change {
+            SettingsChange::Decrease => value.saturating_sub(1),
+            SettingsChange::Increase => (*value + 1).min(max),
+        };
+        changed = true;
+    }
+
+    if changed
+            && let Err(err) = settings.write_to_file() {
+        error!("Could not save current settings. Error: {}", err);
+    }
+}
+
+/// Updates the displayed lock reset text. 
+fn display_lock_reset_settings(
+    settings: Res<Settings>,
+    mut text_query: Query<&mut Text, With<LockResetTextMarker>>,
+) {
+    let Ok(mut text) = text_query.single_mut() else {return;};
+
+    *text = Text::new(lock_reset_to_str(settings.rules.lock_reset));
+}
+
+/// Implements the button functionality for cycling through the lock reset rules. 
+fn update_lock_reset_settings(
+    mut settings: ResMut<Settings>,
+    button_query: Query<&Interaction, (Changed<Interaction>, With<Button>, With<LockResetButton>)>,
+) {
+    let Ok(interaction) = button_query.single() else {return;};
+
+    if *interaction == Interaction::Pressed {
+        let all = engine::model::LockReset::all_lock_reset_array();
+        let index = all.iter().position(|reset| *reset == settings.rules.lock_reset).unwrap_or(0);
+        settings.rules.lock_reset = all[(index + 1) % all.len()];
+
+        if let Err(err) = settings.write_to_file() {
+            error!("Could not save current settings. Error: {}", err);
+        }
+    }
+}
+
+/// Updates the displayed hold rule text. 
+fn display_hold_rule_settings(
+    settings: Res<Settings>,
+    mut text_query: Query<&mut Text, With<HoldRuleTextMarker>>,
+) {
+    let Ok(mut text) = text_query.single_mut() else {return;};
+
+    *text = Text::new(hold_rule_to_str(settings.rules.hold));
+}
+
+/// Implements the button functionality for cycling through the hold rules. 
+fn update_hold_rule_settings(
+    mut settings: ResMut<Settings>,
+    button_query: Query<&Interaction, (Changed<Interaction>, With<Button>, With<HoldRuleButton>)>,
+) {
+    let Ok(interaction) = button_query.single() else {return;};
+
+    if *interaction == Interaction::Pressed {
+        let all = engine::model::HoldRule::all_hold_rule_array();
+        let index = all.iter().position(|hold| *hold == settings.rules.hold).unwrap_or(0);
+        settings.rules.hold = all[(index + 1) % all.len()];
+
+        if let Err(err) = settings.write_to_file() {
+            error!("Could not save current settings. Error: {}", err);
+        }
+    }
+}
+
+/// Updates the displayed seed text. Shows a cursor while the seed is being typed in. 
+fn display_seed_settings(
+    settings: Res<Settings>,
+    mut text_query: Query<&mut Text, With<SeedTextMarker>>,
+    editing_query: Query<(), With<EditingSeed>>,
+) {
+    let Ok(mut text) = text_query.single_mut() else {return;};
 
-            //remove game over screen
-            let Ok(main_div) = main_div_query.single() else {error!("Failed to remove New Game main div!"); return;};
-            commands.entity(main_div).despawn();
+    let seed = match settings.seed {
+        Some(seed) => seed.to_string(),
+        None if editing_query.is_empty() => "Random".to_string(),
+        None => String::new(),
+    };
+    let cursor = if editing_query.is_empty() {""} else {"_"};
+    *text = Text::new(format!("{seed}{cursor}"));
+}
 
-            //set game to be running again
-            is_game_running.0 = engine::scene::AppState::Running;
-        }
-        Interaction::Hovered => {
-            *background_color = BackgroundColor(Color::Srgba(Srgba { red: 1.0, green: 1.0, blue: 1.0, alpha: 0.2 }));
-            //*background_color = BackgroundColor(Color::Srgba(Srgba::WHITE));
+/// Starts and stops typing in the seed when its button is clicked. 
+fn seed_button_listener(
+    mut commands: Commands,
+    button_query: Query<(Entity, &Interaction, Has<EditingSeed>), (Changed<Interaction>, With<Button>, With<SeedButton>)>,
+) {
+    for (entity, interaction, editing) in button_query {
+        if *interaction != Interaction::Pressed {
+            continue;
         }
-        Interaction::None => {
-            *background_color = EMPTY_BACKGROUND_COLOR;
+
+        if editing {
+            commands.entity(entity).remove::<EditingSeed>();
+        } else {
+            commands.entity(entity).insert(EditingSeed);
         }
     }
 }
 
-/// Implements the button functionality for a single keybind area. 
-fn individual_keybind_button_listener(
-    mut button_query: Query<(&TetrisInstruction, &mut BackgroundColor, &Interaction, &KeyBindClickArea), (Changed<Interaction>, With<Button>)>,
+/// Types in the seed with the number keys while it is selected. Backspace removes the last digit, 
+/// Delete goes back to a random seed and Enter finishes typing. 
+fn update_seed_settings(
     mut commands: Commands,
-    waiting_query: Query<Entity, With<WaitingForNewKeyBind>>,
-    clear_all: Res<ClearKeybindClicks>,
-    highlight_one: Res<HighlghtClickedKeybind>,
+    mut settings: ResMut<Settings>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    editing_query: Query<Entity, With<EditingSeed>>,
 ) {
-    for (instruction, mut background_color, interaction, keybind_click_area) in &mut button_query {
-        if *interaction == Interaction::Pressed{
-            //remove previous waiting component
-            if let Ok(entity) = waiting_query.single() {    //theoretically, no more than one should ever exist concurrently
-                commands.entity(entity).despawn();
+    let Ok(entity) = editing_query.single() else {return;};
+    let mut changed = false;
+
+    for key in keyboard_input.get_just_pressed() {
+        match key {
+            KeyCode::Enter | KeyCode::NumpadEnter => {
+                commands.entity(entity).remove::<EditingSeed>();
+            }
+            KeyCode::Backspace => {
+                settings.seed = settings.seed.and_then(|seed| if seed < 10 {None} else {Some(seed / 10)});
+                changed = true;
+            }
+            KeyCode::Delete => {
+                settings.seed = None;
+                changed = true;
             }
+            key => {
+                let Some(digit) = key_code_to_digit(*key) else {continue;};
+                //digits which would overflow the seed are ignored
+                if let Some(seed) = settings.seed.unwrap_or(0).checked_mul(10).and_then(|seed| seed.checked_add(digit)) {
+                    settings.seed = Some(seed);
+                    changed = true;
+                }
+            }
+        }
+    }
 
-            //change the color to signal to the user which field is active
-            background_color.0 = Color::srgb(0.5, 0.5, 0.5);
+    if changed
+            && let Err(err) = settings.write_to_file() {
+        error!("Could not save current settings. Error: {}", err);
+    }
+}
 
-            //spawn a struct which signals not to use button inputs
-            commands.spawn(WaitingForNewKeyBind {
-                primary: keybind_click_area.0,
-                selected_tetris_instruction: *instruction,
-            });
-            commands.run_system(clear_all.0);
-            commands.run_system(highlight_one.0);
-        }
+/// Maps the number keys of the main keyboard and the numpad to their digit. Returns None for every 
+/// other key. 
+fn key_code_to_digit(key_code: KeyCode) -> Option<u64> {
+    match key_code {
+        KeyCode::Digit0 | KeyCode::Numpad0 => Some(0),
+        KeyCode::Digit1 | KeyCode::Numpad1 => Some(1),
+        KeyCode::Digit2 | KeyCode::Numpad2 => Some(2),
+        KeyCode::Digit3 | KeyCode::Numpad3 => Some(3),
+        KeyCode::Digit4 | KeyCode::Numpad4 => Some(4),
+        KeyCode::Digit5 | KeyCode::Numpad5 => Some(5),
+        KeyCode::Digit6 | KeyCode::Numpad6 => Some(6),
+        KeyCode::Digit7 | KeyCode::Numpad7 => Some(7),
+        KeyCode::Digit8 | KeyCode::Numpad8 => Some(8),
+        KeyCode::Digit9 | KeyCode::Numpad9 => Some(9),
+        _ => None,
     }
 }
 
-/// The struct that holds the general settings of the bevy engine game: audio and 
-/// keybinds. 
-#[derive(Resource, Clone, Debug)]
-pub struct Settings {
-    pub music_volume: f32,
-    pub key_binds: KeyBinds,
+/// Updates the displayed field dimension texts. 
+fn display_field_size_settings(
+    settings: Res<Settings>,
+    text_query: Query<(&mut Text, &FieldDimension)>,
+) {
+    for (mut text, dimension) in text_query {
+        let value = match dimension {
+            FieldDimension::Width => settings.rules.field_width,
+            FieldDimension::Height => settings.rules.field_height,
+        };
+        *text = Text::new(format!(" {} ", value));
+    }
 }
 
-impl Settings {
-    /// Tries to save the settings to the data/settings.dat file. Returns Err 
-    /// if any problems appear. 
-    fn write_to_file(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let _ = std::fs::create_dir("./data");
+/// Implements the button functionalities for the decrease and increase field dimension buttons. 
+fn update_field_size_settings(
+    mut settings: ResMut<Settings>,
+    button_query: Query<(&Interaction, &FieldDimension, &SettingsChange), (Changed<Interaction>, With<Button>)>,
+) {
+    let mut changed = false;
 
-        //get a file 
-        let file = std::fs::File::create("data/settings.dat")?;
+    for (interaction, dimension, change) in button_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
 
-        //serialize and write to file
-        serde_cbor::to_writer(file, &self)?;
+        let (value, min, max) = match dimension {
+            FieldDimension::Width => (&mut settings.rules.field_width, engine::model::TETRIS_FIELD_MIN_WIDTH, FIELD_MAX_WIDTH),
+            FieldDimension::Height => (&mut settings.rules.field_height, engine::model::TETRIS_FIELD_MIN_HEIGHT, FIELD_MAX_HEIGHT),
+        };
+        *value = match change {
+            SettingsChange::Decrease => value.saturating_sub(1).max(min),
+            SettingsChange::Increase => (*value + 1).min(max),
+        };
+        changed = true;
+    }
 
-        Ok(())
+    if changed
+            && let Err(err) = settings.write_to_file() {
+        error!("Could not save current settings. Error: {}", err);
     }
+}
 
-    /// Reads the settings from the data/settings.dat file. Returns Err if
-    /// any problems appear. 
-    fn new_from_serialized() -> Result<Self, Box<dyn std::error::Error>> {
-        //open the file
-        let file = std::fs::File::open("data/settings.dat")?;
+/// Maps a [engine::model::randomizer::RandomizerKind] to a user readable string slice. 
+fn randomizer_to_str(randomizer: engine::model::randomizer::RandomizerKind) -> &'static str {
+    match randomizer {
+        engine::model::randomizer::RandomizerKind::SevenBag => "7-Bag (Guideline)",
+        engine::model::randomizer::RandomizerKind::FourteenBag => "14-Bag",
+        engine::model::randomizer::RandomizerKind::Random => "Pure Random",
+        engine::model::randomizer::RandomizerKind::Nes => "NES",
+        engine::model::randomizer::RandomizerKind::Tgm => "TGM",
+        engine::model::randomizer::RandomizerKind::Fixed => "Fixed (data/sequence.txt)",
+    }
+}
 
-        //deserialze 
-        let settings = serde_cbor::from_reader(file)?;
+/// Maps a [engine::model::scoring::ScoringRules] to a user readable string slice. 
+fn scoring_rules_to_str(scoring: engine::model::scoring::ScoringRules) -> &'static str {
+    match scoring {
+        engine::model::scoring::ScoringRules::Guideline => "Guideline",
+        engine::model::scoring::ScoringRules::Nintendo => "Nintendo (NES)",
+    }
+}
 
-        Ok(settings)
+/// Maps a [engine::model::LockReset] to a user readable string slice. 
+fn lock_reset_to_str(lock_reset: engine::model::LockReset) -> &'static str {
+    match lock_reset {
+        engine::model::LockReset::Move => "Move Reset (15 moves)",
+        engine::model::LockReset::Infinite => "Infinite",
+        engine::model::LockReset::Step => "Step Reset",
     }
+}
 
-    /// Reads the settings from the file. If this fails (as it does when first
-    /// starting the game), uses default values instead. 
-    fn from_serialized_or_default() -> Self {
-        //try to 
-        let attempt = Settings::new_from_serialized();
-        match attempt {
-            Ok(settings) => {
-                return settings;
-            }
-            Err(err) => {
-                warn!("Could not load user settings. Using default instead. Error: {}", err);
-            }
-        }
+/// Maps a [engine::model::HoldRule] to a user readable string slice. 
+fn hold_rule_to_str(hold: engine::model::HoldRule) -> &'static str {
+    match hold {
+        engine::model::HoldRule::Once => "Once per Piece",
+        engine::model::HoldRule::Unlimited => "Unlimited",
+        engine::model::HoldRule::Disabled => "Disabled",
+    }
+}
 
-        Settings::default()
+/// Returns the name of the game mode. 
+fn game_mode_to_str(game_mode: engine::scene::GameMode) -> &'static str {
+    match game_mode {
+        engine::scene::GameMode::Single => "Single",
+        engine::scene::GameMode::Versus => "Versus",
     }
 }
 
-impl Serialize for Settings {
-    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
-    where S: serde::Serializer {
-        let mut state = serializer.serialize_struct("Settings", 2)?;    //len = nbr of fields to be serialized
+/// Maps a [engine::model::RotationSystemKind] to a user readable string slice. 
+fn rotation_system_to_str(rotation_system: engine::model::RotationSystemKind) -> &'static str {
+    match rotation_system {
+        engine::model::RotationSystemKind::Srs => "SRS (Guideline)",
+        engine::model::RotationSystemKind::Ars => "ARS (TGM)",
+        engine::model::RotationSystemKind::Nrs => "NRS (NES / Game Boy)",
+        engine::model::RotationSystemKind::Classic => "Classic",
+    }
+}
 
-        state.serialize_field("music_volume", &self.music_volume)?;
-        
-        let serializable_key_binds = KeyBindsSerialized::from(self.key_binds.clone());
-        state.serialize_field("key_binds", &serializable_key_binds)?;
+/// A struct that contains all keybinds for a specific Instruction. 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "InstructionKeyBindSerialized")]
+pub struct InstructionKeyBind {
+    action: TetrisInstruction, 
+    pub bindings: Vec<InputBinding>,
+}
 
-        state.end()
-    }
+/// A helper struct for deserializing an [InstructionKeyBind]. Settings saved before an instruction 
+/// could have any number of bindings contain a primary and a secondary binding instead. 
+#[derive(Deserialize)]
+struct InstructionKeyBindSerialized {
+    action: TetrisInstruction,
+    #[serde(default)]
+    bindings: Vec<InputBindingSerialized>,
+    #[serde(default, alias = "primary_key")]
+    primary: Option<InputBindingSerialized>,
+    #[serde(default, alias = "secondary_key")]
+    secondary: Option<InputBindingSerialized>,
 }
 
-impl<'de> Deserialize<'de> for Settings {
-    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
-    where D: serde::Deserializer<'de> {
-        #[derive(Deserialize, Debug)]
-        struct Helper {
-            music_volume: f32,
-            key_binds: KeyBindsSerialized,
+impl From<InstructionKeyBindSerialized> for InstructionKeyBind {
+    fn from(value: InstructionKeyBindSerialized) -> Self {
+        let bindings = value.primary.into_iter()
+            .chain(value.secondary)
+            .chain(value.bindings)
+            .map(InputBinding::from)
+            .collect();
+
+        Self {
+            action: value.action,
+            bindings,
         }
+    }
+}
 
-        let helper = Helper::deserialize(deserializer)?;
+/// A single input which can be bound to a [TetrisInstruction]: a key, a gamepad button or a direction 
+/// of the left stick of a gamepad. 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InputBinding {
+    Key(KeyCode),
+    Gamepad(GamepadButton),
+    Stick(StickDirection),
+}
 
-        Ok(Settings {
-            music_volume: helper.music_volume,
-            key_binds: helper.key_binds.into(),
-        })
-    }
+/// A direction the left stick of a gamepad can be pushed in. 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StickDirection {
+    Left,
+    Right,
+    Up,
+    Down,
 }
 
-impl Default for Settings {
-    fn default() -> Self {
-        Self { 
-            music_volume: 0.5,
-            key_binds: KeyBinds::default(),
+/// A helper enum for deserializing an [InputBinding]. Settings saved before gamepads could be bound 
+/// contain bare key codes instead. 
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum InputBindingSerialized {
+    Binding(InputBinding),
+    Key(KeyCode),
+}
+
+impl From<InputBindingSerialized> for InputBinding {
+    fn from(value: InputBindingSerialized) -> Self {
+        match value {
+            InputBindingSerialized::Binding(binding) => binding,
+            InputBindingSerialized::Key(key) => InputBinding::Key(key),
         }
     }
 }
 
-/// Updates the displayed volume text. 
-fn display_music_volume_settings(
-    settings: Res<Settings>,
-    mut text_query: Query<&mut Text, With<MusicVolumeTextMarker>>,
-) {
-    let Ok(mut text) = text_query.single_mut() else {return;};
-
-    *text = Text::new(format!(" {:.0} ", settings.music_volume * 100.0));
+/// The gamepad inputs held down this frame and the last frame, of every connected gamepad in the 
+/// order they were connected. Unlike buttons, stick directions have no pressed state of their own. 
+#[derive(Resource, Default)]
+pub struct GamepadBindingState {
+    pressed: Vec<HashSet<InputBinding>>,
+    previously_pressed: Vec<HashSet<InputBinding>>,
 }
 
-/// Implements the button functionalities for the decrease and increase music volume button. s
-fn update_music_volume_settings(
-    mut settings: ResMut<Settings>,
-    decrease_query: Query<&Interaction, (Changed<Interaction>, With<Button>, With<DecreaseMusicVolumeButton>)>,
-    increase_query: Query<&Interaction, (Changed<Interaction>, With<Button>, With<IncreaseMusicVolumeButton>)>,
+/// Collects the pressed buttons and stick directions of all gamepads into the [GamepadBindingState]. 
+fn update_gamepad_binding_state(
+    gamepad_query: Query<(Entity, &Gamepad)>,
+    mut state: ResMut<GamepadBindingState>,
 ) {
-    let mut changed = false;
+    let mut gamepads: Vec<(Entity, &Gamepad)> = gamepad_query.iter().collect();
+    gamepads.sort_by_key(|(entity, _)| *entity);
+
+    let pressed = gamepads.into_iter()
+        .map(|(_, gamepad)| {
+            let mut pressed: HashSet<InputBinding> = gamepad.get_pressed()
+                .map(|button| InputBinding::Gamepad(*button))
+                .collect();
+
+            let stick = gamepad.left_stick();
+            let directions = [
+                (stick.x <= -STICK_THRESHOLD, StickDirection::Left),
+                (stick.x >= STICK_THRESHOLD, StickDirection::Right),
+                (stick.y >= STICK_THRESHOLD, StickDirection::Up),
+                (stick.y <= -STICK_THRESHOLD, StickDirection::Down),
+            ];
+            for (_, direction) in directions.into_iter().filter(|(pushed, _)| *pushed) {
+                pressed.insert(InputBinding::Stick(direction));
+            }
 
-    'decrease: {
-        let Ok(interaction) = decrease_query.single() else {break 'decrease};
+            pressed
+        })
+        .collect();
 
-        if *interaction == Interaction::Pressed {
-            settings.music_volume = (settings.music_volume - 0.1).max(0.0);
-            changed = true;
-        }
-    }
+    state.previously_pressed = std::mem::replace(&mut state.pressed, pressed);
+}
 
-    'increase: {
-        let Ok(interaction) = increase_query.single() else {break 'increase};
+/// Gives access to the state of every [InputBinding]. Every player uses the keyboard and the gamepad 
+/// with the same index as the player. 
+#[derive(SystemParam)]
+pub struct BindingInput<'w> {
+    keyboard_input: Res<'w, ButtonInput<KeyCode>>,
+    gamepads: Res<'w, GamepadBindingState>,
+}
 
-        if *interaction == Interaction::Pressed {
-            settings.music_volume = (settings.music_volume + 0.1).min(1.0);
-            changed = true;
+impl BindingInput<'_> {
+    /// Returns true if the binding is held down by the given player. 
+    pub fn pressed(&self, binding: InputBinding, player: usize) -> bool {
+        match binding {
+            InputBinding::Key(key) => self.keyboard_input.pressed(key),
+            _ => self.gamepads.pressed.get(player).is_some_and(|pressed| pressed.contains(&binding)),
         }
     }
 
-    if changed {
-        if let Err(err) = settings.write_to_file() {
-            error!("Could not save current settings. Error: {}", err);
+    /// Returns true if the binding has been pressed by the given player this frame. 
+    pub fn just_pressed(&self, binding: InputBinding, player: usize) -> bool {
+        match binding {
+            InputBinding::Key(key) => self.keyboard_input.just_pressed(key),
+            _ => self.pressed(binding, player) && 
+                !self.gamepads.previously_pressed.get(player).is_some_and(|pressed| pressed.contains(&binding)),
         }
     }
-}
-
-/// Updates the primary key bind settings button texts with the values found in the settings. 
-fn update_primary_key_bind_settings(
-    primary_text_query: Query<(&TetrisInstruction, &mut Text), With<FirstKeyBindTextMarker>>,
-    settings: Res<Settings>,
-) {
-    for (action, mut text) in primary_text_query {
-        let instruction_key_bind = settings.key_binds.get(action);
-        *text = Text::new(key_code_to_str(instruction_key_bind.primary_key));
-    }
-}
 
-/// Updates the secondary key bind settings button texts with the values found in the settings. 
-fn update_secondary_key_bind_settings(
-    secondary_text_query: Query<(&TetrisInstruction, &mut Text), With<SecondKeyBindTextMarker>>,
-    settings: Res<Settings>,
-) {
-    for (action, mut text) in secondary_text_query {
-        let instruction_key_bind =  settings.key_binds.get(action);
-        if let Some(secondary_key) = instruction_key_bind.secondary_key {
-            *text = Text::new(key_code_to_str(secondary_key));
-        } else {
-            *text = Text::new("   ");
+    /// Returns any binding pressed this frame on the keyboard or any gamepad. 
+    pub fn any_just_pressed(&self) -> Option<InputBinding> {
+        if let Some(key) = self.keyboard_input.get_just_pressed().next() {
+            return Some(InputBinding::Key(*key));
         }
-    }
-}
 
-/// A struct that contains all keybinds for a specific Instruction. 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
-pub struct InstructionKeyBind {
-    action: TetrisInstruction, 
-    pub primary_key: KeyCode,
-    pub secondary_key: Option<KeyCode>,
+        self.gamepads.pressed.iter()
+            .zip(self.gamepads.previously_pressed.iter().chain(std::iter::repeat(&HashSet::new())))
+            .find_map(|(pressed, previously_pressed)| pressed.difference(previously_pressed).next().copied())
+    }
 }
 
 /// Differentiates which kind of instructions specific to a tetris game can give. 
@@ -914,15 +2760,80 @@ pub enum TetrisInstruction {
     Right, 
     RotateCounter,
     RotateClock, 
+    /// Spins the active piece by half a turn. 
+    Rotate180,
     Store, 
+    /// Gives up the game. 
+    Forfeit,
+    /// Pauses and resumes the game. Also cancels assigning a new key bind. 
+    Pause,
+    /// Starts a new game once held down for a moment. 
+    QuickRestart,
+    /// Shows or hides the ghost piece. 
+    ToggleGhost,
+}
+
+impl TetrisInstruction {
+    /// Returns an array of all instructions given to the game, in the order they are carried out within a 
+    /// single frame. 
+    pub fn all_instructions_array() -> [Self; 9] {
+        [
+            Self::Left,
+            Self::Right,
+            Self::Drop,
+            Self::FullDrop,
+            Self::RotateCounter,
+            Self::RotateClock,
+            Self::Rotate180,
+            Self::Store,
+            Self::Forfeit,
+        ]
+    }
+
+    /// Returns whether the instruction is carried out by the scene instead of being given to the game. 
+    pub fn is_meta(self) -> bool {
+        matches!(self, Self::Pause | Self::QuickRestart | Self::ToggleGhost)
+    }
 }
 
 impl InstructionKeyBind {
-    fn new(instruction: TetrisInstruction, primary: KeyCode, secondary: Option<KeyCode>) -> Self {
+    fn new(instruction: TetrisInstruction, bindings: Vec<InputBinding>) -> Self {
         Self {
             action: instruction,
-            primary_key: primary,
-            secondary_key: secondary,
+            bindings,
+        }
+    }
+
+    /// Returns true if one of the bindings has been pressed by the given player this frame. 
+    pub fn just_pressed(&self, input: &BindingInput, player: usize) -> bool {
+        self.bindings.iter().any(|binding| input.just_pressed(*binding, player))
+    }
+
+    /// Returns true if one of the bindings is currently held down by the given player. 
+    pub fn pressed(&self, input: &BindingInput, player: usize) -> bool {
+        self.bindings.iter().any(|binding| input.pressed(*binding, player))
+    }
+}
+
+/// How held keys move the active piece. 
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Handling {
+    /// The delayed auto shift, how long left or right has to be held before the piece starts moving 
+    /// on its own. 
+    pub das_millis: u32,
+    /// The auto repeat rate, the time between two moves after the delayed auto shift. With 0 the piece 
+    /// moves all the way to the wall at once. 
+    pub arr_millis: u32,
+    /// How many times faster than gravity the piece falls while drop is held. 
+    pub soft_drop_factor: u32,
+}
+
+impl Default for Handling {
+    fn default() -> Self {
+        Self {
+            das_millis: 170,
+            arr_millis: 50,
+            soft_drop_factor: 20,
         }
     }
 }
@@ -934,11 +2845,11 @@ pub struct KeyBinds {
 }
 
 impl KeyBinds {
-    /// Returns a copy of the [InstructionKeyBind] for the given [TetrisInstruction]. 
-    pub fn get(&self, instruction: &TetrisInstruction) -> InstructionKeyBind {
+    /// Returns the [InstructionKeyBind] for the given [TetrisInstruction]. 
+    pub fn get(&self, instruction: &TetrisInstruction) -> &InstructionKeyBind {
         for instruction_key_bind in &self.key_binds {
             if instruction_key_bind.action == *instruction {
-                return *instruction_key_bind;
+                return instruction_key_bind;
             }
         }
 
@@ -965,17 +2876,103 @@ impl PartialEq for InstructionKeyBind {
 
 impl Default for KeyBinds {
     fn default() -> Self {
-        let key_binds = vec![
-            InstructionKeyBind::new(TetrisInstruction::Drop, KeyCode::KeyS, None),
-            InstructionKeyBind::new(TetrisInstruction::FullDrop, KeyCode::Space, None),
-            InstructionKeyBind::new(TetrisInstruction::Left, KeyCode::KeyA, None),
-            InstructionKeyBind::new(TetrisInstruction::Right, KeyCode::KeyD, None),
-            InstructionKeyBind::new(TetrisInstruction::RotateCounter, KeyCode::KeyQ, None),
-            InstructionKeyBind::new(TetrisInstruction::RotateClock, KeyCode::KeyE, None),
-            InstructionKeyBind::new(TetrisInstruction::Store, KeyCode::KeyW, None),
-        ];
+        KeyBindPreset::Wasd.key_binds()
+    }
+}
 
-        Self { 
+impl KeyBinds {
+    /// Returns the default key binds of the second player of a versus game, which leave the keys of the 
+    /// first player free. 
+    pub fn versus_default() -> Self {
+        KeyBindPreset::Arrows.key_binds()
+    }
+}
+
+/// The predefined sets of key binds selectable in the key bind settings. 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyBindPreset {
+    /// The left hand moves with WASD and rotates with Q, E and F. 
+    Wasd,
+    /// The right hand moves with the arrow keys, rotates with Comma, Period and Slash and pauses with P. 
+    Arrows,
+    /// The layout recommended by the Tetris guideline. 
+    Guideline,
+}
+
+impl KeyBindPreset {
+    /// Returns the key binds of the preset. Every preset binds the D-pad and face buttons of a gamepad 
+    /// the same way. 
+    pub fn key_binds(self) -> KeyBinds {
+        let keys: [(TetrisInstruction, &[KeyCode]); 12] = match self {
+            Self::Wasd => [
+                (TetrisInstruction::Drop, &[KeyCode::KeyS]),
+                (TetrisInstruction::FullDrop, &[KeyCode::Space]),
+                (TetrisInstruction::Left, &[KeyCode::KeyA]),
+                (TetrisInstruction::Right, &[KeyCode::KeyD]),
+                (TetrisInstruction::RotateCounter, &[KeyCode::KeyQ]),
+                (TetrisInstruction::RotateClock, &[KeyCode::KeyE]),
+                (TetrisInstruction::Rotate180, &[KeyCode::KeyF]),
+                (TetrisInstruction::Store, &[KeyCode::KeyW]),
+                (TetrisInstruction::Forfeit, &[]),
+                (TetrisInstruction::Pause, &[KeyCode::Escape]),
+                (TetrisInstruction::QuickRestart, &[KeyCode::KeyR]),
+                (TetrisInstruction::ToggleGhost, &[KeyCode::KeyG]),
+            ],
+            Self::Arrows => [
+                (TetrisInstruction::Drop, &[KeyCode::ArrowDown]),
+                (TetrisInstruction::FullDrop, &[KeyCode::Enter]),
+                (TetrisInstruction::Left, &[KeyCode::ArrowLeft]),
+                (TetrisInstruction::Right, &[KeyCode::ArrowRight]),
+                (TetrisInstruction::RotateCounter, &[KeyCode::Comma]),
+                (TetrisInstruction::RotateClock, &[KeyCode::Period]),
+                (TetrisInstruction::Rotate180, &[KeyCode::Slash]),
+                (TetrisInstruction::Store, &[KeyCode::ArrowUp]),
+                (TetrisInstruction::Forfeit, &[]),
+                (TetrisInstruction::Pause, &[KeyCode::KeyP]),
+                (TetrisInstruction::QuickRestart, &[KeyCode::Backspace]),
+                (TetrisInstruction::ToggleGhost, &[]),
+            ],
+            Self::Guideline => [
+                (TetrisInstruction::Drop, &[KeyCode::ArrowDown]),
+                (TetrisInstruction::FullDrop, &[KeyCode::Space]),
+                (TetrisInstruction::Left, &[KeyCode::ArrowLeft]),
+                (TetrisInstruction::Right, &[KeyCode::ArrowRight]),
+                (TetrisInstruction::RotateCounter, &[KeyCode::KeyZ, KeyCode::ControlLeft]),
+                (TetrisInstruction::RotateClock, &[KeyCode::ArrowUp, KeyCode::KeyX]),
+                (TetrisInstruction::Rotate180, &[KeyCode::KeyA]),
+                (TetrisInstruction::Store, &[KeyCode::ShiftLeft, KeyCode::KeyC]),
+                (TetrisInstruction::Forfeit, &[]),
+                (TetrisInstruction::Pause, &[KeyCode::Escape, KeyCode::F1]),
+                (TetrisInstruction::QuickRestart, &[KeyCode::KeyR]),
+                (TetrisInstruction::ToggleGhost, &[KeyCode::KeyG]),
+            ],
+        };
+
+        let key_binds = keys.into_iter()
+            .map(|(instruction, keys)| {
+                let gamepad_buttons: &[GamepadButton] = match instruction {
+                    TetrisInstruction::Drop => &[GamepadButton::DPadDown],
+                    TetrisInstruction::FullDrop => &[GamepadButton::DPadUp],
+                    TetrisInstruction::Left => &[GamepadButton::DPadLeft],
+                    TetrisInstruction::Right => &[GamepadButton::DPadRight],
+                    TetrisInstruction::RotateCounter => &[GamepadButton::West],
+                    TetrisInstruction::RotateClock => &[GamepadButton::South],
+                    TetrisInstruction::Rotate180 => &[GamepadButton::North],
+                    TetrisInstruction::Store => &[GamepadButton::LeftTrigger],
+                    TetrisInstruction::Pause => &[GamepadButton::Start],
+                    TetrisInstruction::QuickRestart => &[GamepadButton::Select],
+                    TetrisInstruction::Forfeit | TetrisInstruction::ToggleGhost => &[],
+                };
+                let bindings = keys.iter()
+                    .map(|key| InputBinding::Key(*key))
+                    .chain(gamepad_buttons.iter().map(|button| InputBinding::Gamepad(*button)))
+                    .collect();
+
+                InstructionKeyBind::new(instruction, bindings)
+            })
+            .collect();
+
+        KeyBinds {
             key_binds,
         }
     }
@@ -996,15 +2993,30 @@ impl From<KeyBinds> for KeyBindsSerialized {
     }
 }
 
-impl From<KeyBindsSerialized> for KeyBinds {
-    fn from(value: KeyBindsSerialized) -> Self {
-        let mut list = value.key_binds;
-        let mut default = KeyBinds::default();
-
-        for elem in default.key_binds {
-            if !list.contains(&elem) {
-                list.push(elem);
+impl KeyBindsSerialized {
+    /// Turns the logged key binds into [KeyBinds], taking every missing [TetrisInstruction] from 
+    /// the given defaults. A default binding is left out if the logged key binds already use it or if 
+    /// it is one of the given keys of the other player. 
+    fn with_defaults(self, mut default: KeyBinds, other_player_keys: &HashSet<InputBinding>) -> KeyBinds {
+        let mut list = self.key_binds;
+        let mut taken: HashSet<InputBinding> = list.iter()
+            .flat_map(|key_bind| key_bind.bindings.iter().copied())
+            .chain(other_player_keys.iter().copied())
+            .collect();
+
+        for mut elem in default.key_binds {
+            if list.contains(&elem) {
+                continue;
             }
+
+            elem.bindings.retain(|binding| {
+                let free = taken.insert(*binding);
+                if !free {
+                    warn!("{} is already bound, leaving it out of the default bindings of {:?}.", binding_to_str(*binding), elem.action);
+                }
+                free
+            });
+            list.push(elem);
         }
 
         default.key_binds = list;
@@ -1013,6 +3025,53 @@ impl From<KeyBindsSerialized> for KeyBinds {
     }
 }
 
+/// Returns all keys bound to any of the given key binds. Gamepad buttons and stick directions are left 
+/// out, as every player has their own gamepad. 
+fn bound_keys(key_binds: &[InstructionKeyBind]) -> HashSet<InputBinding> {
+    key_binds.iter()
+        .flat_map(|key_bind| key_bind.bindings.iter().copied())
+        .filter(|binding| matches!(binding, InputBinding::Key(_)))
+        .collect()
+}
+
+/// Maps an [InputBinding] to a string slice, see [key_code_to_str]. 
+fn binding_to_str(binding: InputBinding) -> &'static str {
+    match binding {
+        InputBinding::Key(key_code) => key_code_to_str(key_code),
+        InputBinding::Gamepad(button) => gamepad_button_to_str(button),
+        InputBinding::Stick(StickDirection::Left) => "Stick Left",
+        InputBinding::Stick(StickDirection::Right) => "Stick Right",
+        InputBinding::Stick(StickDirection::Up) => "Stick Up",
+        InputBinding::Stick(StickDirection::Down) => "Stick Down",
+    }
+}
+
+/// Maps a [GamepadButton] to a string slice, see [key_code_to_str]. 
+fn gamepad_button_to_str(button: GamepadButton) -> &'static str {
+    match button {
+        GamepadButton::South => "Pad South",
+        GamepadButton::East => "Pad East",
+        GamepadButton::North => "Pad North",
+        GamepadButton::West => "Pad West",
+        GamepadButton::C => "Pad C",
+        GamepadButton::Z => "Pad Z",
+        GamepadButton::LeftTrigger => "Pad LB",
+        GamepadButton::LeftTrigger2 => "Pad LT",
+        GamepadButton::RightTrigger => "Pad RB",
+        GamepadButton::RightTrigger2 => "Pad RT",
+        GamepadButton::Select => "Pad Select",
+        GamepadButton::Start => "Pad Start",
+        GamepadButton::Mode => "Pad Mode",
+        GamepadButton::LeftThumb => "Pad L Stick",
+        GamepadButton::RightThumb => "Pad R Stick",
+        GamepadButton::DPadUp => "D-Pad Up",
+        GamepadButton::DPadDown => "D-Pad Down",
+        GamepadButton::DPadLeft => "D-Pad Left",
+        GamepadButton::DPadRight => "D-Pad Right",
+        GamepadButton::Other(_) => "Pad Other",
+    }
+}
+
 /// Maps a [KeyCode] to a string slice. Substitutes the Display function as it is not 
 /// implemented and the debug format is not user readable. 
 fn key_code_to_str(key_code: KeyCode) -> &'static str {
@@ -1082,6 +3141,9 @@ fn key_code_to_str(key_code: KeyCode) -> &'static str {
         KeyCode::PrintScreen => "Print",
         KeyCode::ScrollLock => "Scroll Lock",
         KeyCode::Pause => "Pause",
+        KeyCode::Comma => "Comma",
+        KeyCode::Period => "Period",
+        KeyCode::Slash => "Slash",
 
         KeyCode::F1 => "F1",
         KeyCode::F2 => "F2",
@@ -1144,6 +3206,88 @@ fn key_code_to_str(key_code: KeyCode) -> &'static str {
 /// the user to save as a new keybind. 
 #[derive(Component, Clone, Copy, Debug)]
 pub struct WaitingForNewKeyBind {
-    pub primary: u32,
+    /// The index of the binding to replace, the index behind the last binding adds a new one. 
+    pub index: usize,
     pub selected_tetris_instruction: TetrisInstruction,
+    pub player: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bindings(settings: &Settings, player: usize, instruction: TetrisInstruction) -> &[InputBinding] {
+        &settings.player_key_binds(player).get(&instruction).bindings
+    }
+
+    #[test]
+    fn binding_a_key_of_another_instruction_swaps_the_bindings() {
+        let mut settings = Settings::default();
+
+        settings.bind(0, TetrisInstruction::Left, 0, InputBinding::Key(KeyCode::KeyD));
+
+        assert_eq!(bindings(&settings, 0, TetrisInstruction::Left), [InputBinding::Key(KeyCode::KeyD), InputBinding::Gamepad(GamepadButton::DPadLeft)]);
+        assert_eq!(bindings(&settings, 0, TetrisInstruction::Right), [InputBinding::Key(KeyCode::KeyA), InputBinding::Gamepad(GamepadButton::DPadRight)]);
+    }
+
+    #[test]
+    fn adding_a_key_of_another_instruction_removes_it_there() {
+        let mut settings = Settings::default();
+
+        settings.bind(0, TetrisInstruction::Left, 2, InputBinding::Key(KeyCode::KeyE));
+
+        assert_eq!(
+            bindings(&settings, 0, TetrisInstruction::Left), 
+            [InputBinding::Key(KeyCode::KeyA), InputBinding::Gamepad(GamepadButton::DPadLeft), InputBinding::Key(KeyCode::KeyE)],
+        );
+        assert_eq!(bindings(&settings, 0, TetrisInstruction::RotateClock), [InputBinding::Gamepad(GamepadButton::South)]);
+    }
+
+    #[test]
+    fn binding_a_key_of_the_other_player_swaps_it_there() {
+        let mut settings = Settings::default();
+
+        settings.bind(0, TetrisInstruction::Left, 0, InputBinding::Key(KeyCode::ArrowLeft));
+
+        assert_eq!(bindings(&settings, 0, TetrisInstruction::Left)[0], InputBinding::Key(KeyCode::ArrowLeft));
+        assert_eq!(bindings(&settings, 1, TetrisInstruction::Left), [InputBinding::Key(KeyCode::KeyA), InputBinding::Gamepad(GamepadButton::DPadLeft)]);
+    }
+
+    #[test]
+    fn binding_a_gamepad_button_leaves_the_other_player_alone() {
+        let mut settings = Settings::default();
+
+        settings.bind(0, TetrisInstruction::Drop, 0, InputBinding::Gamepad(GamepadButton::DPadLeft));
+
+        assert_eq!(bindings(&settings, 0, TetrisInstruction::Left), [InputBinding::Key(KeyCode::KeyA), InputBinding::Key(KeyCode::KeyS)]);
+        assert_eq!(bindings(&settings, 1, TetrisInstruction::Left), [InputBinding::Key(KeyCode::ArrowLeft), InputBinding::Gamepad(GamepadButton::DPadLeft)]);
+    }
+
+    #[test]
+    fn setting_key_binds_removes_their_keys_from_the_other_player() {
+        let mut settings = Settings::default();
+
+        settings.set_key_binds(1, KeyBindPreset::Guideline.key_binds());
+
+        assert_eq!(bindings(&settings, 1, TetrisInstruction::Rotate180), [InputBinding::Key(KeyCode::KeyA), InputBinding::Gamepad(GamepadButton::North)]);
+        assert_eq!(bindings(&settings, 0, TetrisInstruction::Left), [InputBinding::Gamepad(GamepadButton::DPadLeft)]);
+        assert_eq!(bindings(&settings, 0, TetrisInstruction::FullDrop), [InputBinding::Gamepad(GamepadButton::DPadUp)]);
+        assert_eq!(bindings(&settings, 0, TetrisInstruction::Right), [InputBinding::Key(KeyCode::KeyD), InputBinding::Gamepad(GamepadButton::DPadRight)]);
+    }
+
+    #[test]
+    fn missing_instructions_are_filled_with_free_default_bindings() {
+        let logged = KeyBindsSerialized {
+            key_binds: vec![InstructionKeyBind::new(TetrisInstruction::Left, vec![InputBinding::Key(KeyCode::KeyS)])],
+        };
+        let other_player_keys = HashSet::from([InputBinding::Key(KeyCode::KeyD)]);
+
+        let key_binds = logged.with_defaults(KeyBinds::default(), &other_player_keys);
+
+        assert_eq!(key_binds.key_binds.len(), KeyBinds::default().key_binds.len());
+        assert_eq!(key_binds.get(&TetrisInstruction::Left).bindings, [InputBinding::Key(KeyCode::KeyS)]);
+        assert_eq!(key_binds.get(&TetrisInstruction::Drop).bindings, [InputBinding::Gamepad(GamepadButton::DPadDown)]);
+        assert_eq!(key_binds.get(&TetrisInstruction::Right).bindings, [InputBinding::Gamepad(GamepadButton::DPadRight)]);
+        assert_eq!(key_binds.get(&TetrisInstruction::Store).bindings, [InputBinding::Key(KeyCode::KeyW), InputBinding::Gamepad(GamepadButton::LeftTrigger)]);
+    }
 }